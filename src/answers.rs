@@ -0,0 +1,82 @@
+use crate::parse::{number, ParseError};
+use std::collections::BTreeMap;
+
+/// Expected answers by day, input file and part, read from a manifest with
+/// one `<day> <part> <input> <answer>` line per answer. Blank lines and
+/// lines starting with `#` are skipped.
+#[derive(Debug, Default)]
+pub struct Answers(BTreeMap<(u8, String, u8), String>);
+
+impl Answers {
+    /// Parses the manifest, skipping blank and comment lines.
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut answers = BTreeMap::new();
+        for (i, l) in input.lines().enumerate() {
+            if l.trim().is_empty() || l.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<_> = l.split_whitespace().collect();
+            let (day, part, path, answer) = match fields[..] {
+                [day, part, path, answer] => (day, part, path, answer),
+                _ => {
+                    let expected = "`<day> <part> <input> <answer>`";
+                    return Err(ParseError::at(i, l, l, expected));
+                }
+            };
+            let day: u8 = number(i, l, day)?;
+            if !(1..=25).contains(&day) {
+                return Err(ParseError::at(i, l, fields[0], "a day from 1 to 25"));
+            }
+            let part: u8 = number(i, l, part)?;
+            if !(1..=2).contains(&part) {
+                return Err(ParseError::at(i, l, fields[1], "part 1 or 2"));
+            }
+            let key = (day, path.to_string(), part);
+            if answers.insert(key, answer.to_string()).is_some() {
+                let expected = "one answer per day, part and input";
+                return Err(ParseError::at(i, l, l, expected));
+            }
+        }
+        Ok(Answers(answers))
+    }
+
+    /// The expected answer for `part` of `day` on the input file `path`.
+    pub fn get(&self, day: u8, path: &str, part: u8) -> Option<&str> {
+        self.0
+            .get(&(day, path.to_string(), part))
+            .map(String::as_str)
+    }
+
+    /// The input files with answers for `day`, in order.
+    pub fn inputs(&self, day: u8) -> Vec<&str> {
+        let mut inputs: Vec<_> = self
+            .0
+            .keys()
+            .filter(|(d, _, _)| *d == day)
+            .map(|(_, path, _)| path.as_str())
+            .collect();
+        inputs.dedup();
+        inputs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Answers;
+    #[test]
+    fn manifest() {
+        let text = "# day part input answer\n\n\
+                    1 1 input/day01 786811\n\
+                    1 2 input/day01 199068980\n\
+                    1 1 input/example01 514579\n";
+        let answers = Answers::parse(text).unwrap();
+        assert_eq!(answers.get(1, "input/day01", 2), Some("199068980"));
+        assert_eq!(answers.get(1, "input/example01", 2), None);
+        assert_eq!(answers.inputs(1), ["input/day01", "input/example01"]);
+        assert!(answers.inputs(2).is_empty());
+        let err = Answers::parse("1 3 input/day01 5").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 3));
+        let err = Answers::parse("1 1 a 5\n1 1 a 6").unwrap_err();
+        assert_eq!(err.line, 2);
+    }
+}
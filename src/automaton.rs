@@ -0,0 +1,626 @@
+use crate::parse::{number, ParseError};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display};
+use std::hash::Hash;
+use std::mem;
+use std::str::FromStr;
+
+/// Which cells neighbor which.
+pub trait Topology {
+    /// A cell of the topology.
+    type Cell: Copy + Eq + Hash;
+    /// The neighbors of a cell.
+    type Neighbors: IntoIterator<Item = Self::Cell>;
+
+    /// The cells neighboring `cell`.
+    fn neighbors(&self, cell: Self::Cell) -> Self::Neighbors;
+
+    /// Every cell, for finite topologies. For infinite ones only cells next
+    /// to a live cell are updated, so nothing is born with 0 neighbors.
+    fn cells(&self) -> Option<&[Self::Cell]> {
+        None
+    }
+}
+
+/// Numbers of live neighbors for which a dead cell is born and a live cell
+/// survives, as in Life's B3/S23.
+///
+/// Parses from and prints as B/S notation. Counts are single digits
+/// (`B36/S23`) unless a list has commas or ranges, as in `B5-7/S10,12`,
+/// for neighborhoods with more than 9 cells.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rule {
+    birth: Vec<usize>,
+    survival: Vec<usize>,
+}
+
+impl Rule {
+    /// A rule with the given birth and survival counts, in any order.
+    pub fn new(
+        birth: impl IntoIterator<Item = usize>,
+        survival: impl IntoIterator<Item = usize>,
+    ) -> Self {
+        let sorted = |counts: Vec<usize>| {
+            let mut counts = counts;
+            counts.sort_unstable();
+            counts.dedup();
+            counts
+        };
+        Rule {
+            birth: sorted(birth.into_iter().collect()),
+            survival: sorted(survival.into_iter().collect()),
+        }
+    }
+
+    /// Conway's Game of Life, B3/S23.
+    pub fn life() -> Self {
+        Rule::new([3], [2, 3])
+    }
+
+    /// Parses B/S notation for an infinite topology, where a birth on 0
+    /// neighbors would have to fill all of space at once.
+    pub fn parse_infinite(s: &str) -> Result<Self, ParseError> {
+        parse_rule(s, 1)
+    }
+
+    /// Whether a cell is live next round.
+    pub fn next(&self, alive: bool, neighbors: usize) -> bool {
+        if alive {
+            self.survival.contains(&neighbors)
+        } else {
+            self.birth.contains(&neighbors)
+        }
+    }
+}
+
+/// Parses the counts after the `B` or `S` of `text`, a slice of `rule`.
+/// Counts, or the starts of ranges, below `min` are errors.
+fn parse_counts(rule: &str, text: &str, min: usize) -> Result<Vec<usize>, ParseError> {
+    let at_least = |count: usize, slice: &str| {
+        if count < min {
+            Err(ParseError::at(0, rule, slice, format!("a count of at least {}", min)))
+        } else {
+            Ok(count)
+        }
+    };
+    if !text.contains([',', '-']) {
+        return text
+            .char_indices()
+            .map(|(i, c)| {
+                let slice = &text[i..i + c.len_utf8()];
+                let digit = c
+                    .to_digit(10)
+                    .ok_or_else(|| ParseError::at(0, rule, slice, "a digit"))?;
+                at_least(digit as usize, slice)
+            })
+            .collect();
+    }
+    let mut counts = Vec::new();
+    for item in text.split(',') {
+        match item.split_once('-') {
+            Some((from, to)) => {
+                let from = at_least(number(0, rule, from)?, from)?;
+                counts.extend(from..=number(0, rule, to)?);
+            }
+            None => counts.push(at_least(number(0, rule, item)?, item)?),
+        }
+    }
+    Ok(counts)
+}
+
+/// Parses B/S notation, with births on at least `min_birth` neighbors.
+fn parse_rule(s: &str, min_birth: usize) -> Result<Rule, ParseError> {
+    let (birth, survival) = s
+        .split_once('/')
+        .ok_or_else(|| ParseError::at(0, s, &s[s.len()..], "`/`"))?;
+    let birth = birth
+        .strip_prefix(['B', 'b'])
+        .ok_or_else(|| ParseError::at(0, s, birth, "`B`"))?;
+    let survival = survival
+        .strip_prefix(['S', 's'])
+        .ok_or_else(|| ParseError::at(0, s, survival, "`S`"))?;
+    Ok(Rule::new(parse_counts(s, birth, min_birth)?, parse_counts(s, survival, 0)?))
+}
+
+impl FromStr for Rule {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        parse_rule(s, 0)
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts = |counts: &[usize]| {
+            let digits: Vec<_> = counts.iter().map(usize::to_string).collect();
+            if counts.iter().any(|&c| c > 9) {
+                digits.join(",")
+            } else {
+                digits.concat()
+            }
+        };
+        write!(f, "B{}/S{}", counts(&self.birth), counts(&self.survival))
+    }
+}
+
+/// The live cells of a topology, updated together by a rule each step.
+#[derive(Clone, Debug)]
+pub struct Automaton<T: Topology> {
+    topology: T,
+    rule: Rule,
+    live: HashSet<T::Cell>,
+}
+
+impl<T: Topology> Automaton<T> {
+    /// An automaton on `topology` with the `live` cells.
+    pub fn new(
+        topology: T,
+        rule: Rule,
+        live: impl IntoIterator<Item = T::Cell>,
+    ) -> Self {
+        let live = live.into_iter().collect();
+        Automaton { topology, rule, live }
+    }
+
+    /// The cells and how they neighbor.
+    pub fn topology(&self) -> &T {
+        &self.topology
+    }
+    /// The rule the cells follow.
+    pub fn rule(&self) -> &Rule {
+        &self.rule
+    }
+    /// The live cells.
+    pub fn live(&self) -> &HashSet<T::Cell> {
+        &self.live
+    }
+    /// Whether `cell` is live.
+    pub fn is_live(&self, cell: T::Cell) -> bool {
+        self.live.contains(&cell)
+    }
+    /// Number of live cells.
+    pub fn len(&self) -> usize {
+        self.live.len()
+    }
+    /// Whether no cell is live.
+    pub fn is_empty(&self) -> bool {
+        self.live.is_empty()
+    }
+
+    /// Updates every cell at once.
+    pub fn step(&mut self) {
+        let mut counts: HashMap<T::Cell, usize> = HashMap::new();
+        for &cell in &self.live {
+            for n in self.topology.neighbors(cell) {
+                *counts.entry(n).or_default() += 1;
+            }
+        }
+        let count = |cell| counts.get(&cell).copied().unwrap_or(0);
+        let next = match self.topology.cells() {
+            Some(cells) => cells
+                .iter()
+                .copied()
+                .filter(|&c| self.rule.next(self.live.contains(&c), count(c)))
+                .collect(),
+            None => counts
+                .keys()
+                .chain(self.live.iter().filter(|c| !counts.contains_key(c)))
+                .copied()
+                .filter(|&c| self.rule.next(self.live.contains(&c), count(c)))
+                .collect(),
+        };
+        self.live = next;
+    }
+}
+
+/// How an automaton settled once its live cells repeated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Settled {
+    /// Nothing changes after `steps` steps.
+    Stable {
+        /// The first step after which nothing changes.
+        steps: usize,
+    },
+    /// From step `start` on, the cells repeat every `period` steps.
+    Cycle {
+        /// The first step whose cells come round again.
+        start: usize,
+        /// Steps before the cells repeat.
+        period: usize,
+    },
+}
+
+impl Settled {
+    fn new(start: usize, period: usize) -> Self {
+        match period {
+            1 => Settled::Stable { steps: start },
+            period => Settled::Cycle { start, period },
+        }
+    }
+}
+
+/// Brent's cycle detection over the states `advance` moves `start` through,
+/// holding two states at a time. Returns the state at step `start + period`
+/// of the cycle found, or `None` if that is more than `limit` steps away.
+/// Looking for the period may take up to `3 * limit` steps.
+fn find_cycle<S: Clone + PartialEq>(
+    start: S,
+    limit: usize,
+    mut advance: impl FnMut(&mut S),
+) -> Option<(S, Settled)> {
+    let budget = limit.saturating_mul(3);
+    if budget == 0 {
+        return None;
+    }
+    let mut tortoise = start.clone();
+    let mut hare = start.clone();
+    advance(&mut hare);
+    let (mut steps, mut power, mut period) = (1, 1, 1);
+    while hare != tortoise {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        if steps == budget {
+            return None;
+        }
+        advance(&mut hare);
+        steps += 1;
+        period += 1;
+    }
+    if period > limit {
+        return None;
+    }
+    let mut tortoise = start;
+    let mut hare = tortoise.clone();
+    for _ in 0..period {
+        advance(&mut hare);
+    }
+    let mut first = 0;
+    while tortoise != hare {
+        if first + period == limit {
+            return None;
+        }
+        advance(&mut tortoise);
+        advance(&mut hare);
+        first += 1;
+    }
+    Some((hare, Settled::new(first, period)))
+}
+
+impl<T: Topology> Automaton<T> {
+    /// Steps until the live cells are the same as after an earlier step,
+    /// giving up unless that happens within `limit` steps. For a cycle the
+    /// automaton is left at step `start + period`.
+    pub fn settle(&mut self, limit: usize) -> Option<Settled> {
+        let start = self.live.clone();
+        let (live, settled) = find_cycle(start, limit, |live| {
+            mem::swap(&mut self.live, live);
+            self.step();
+            mem::swap(&mut self.live, live);
+        })?;
+        self.live = live;
+        Some(settled)
+    }
+}
+
+/// A finite topology compiled to cell indices, with the neighbors of every
+/// cell worked out once.
+#[derive(Clone, Debug)]
+pub struct Graph<C> {
+    cells: Vec<C>,
+    index: HashMap<C, usize>,
+    neighbors: Vec<Vec<usize>>,
+}
+
+impl<C: Copy + Eq + Hash> Graph<C> {
+    /// Panics if `topology` is infinite.
+    pub fn new<T: Topology<Cell = C>>(topology: &T) -> Self {
+        let cells = topology.cells().expect("topology is infinite").to_vec();
+        let index: HashMap<_, _> =
+            cells.iter().enumerate().map(|(i, &c)| (c, i)).collect();
+        let neighbors = cells
+            .iter()
+            .map(|&c| {
+                topology
+                    .neighbors(c)
+                    .into_iter()
+                    .filter_map(|n| index.get(&n).copied())
+                    .collect()
+            })
+            .collect();
+        Graph { cells, index, neighbors }
+    }
+    /// The cells, in index order.
+    pub fn cells(&self) -> &[C] {
+        &self.cells
+    }
+    /// The index of `cell`, if it is in the graph.
+    pub fn index(&self, cell: C) -> Option<usize> {
+        self.index.get(&cell).copied()
+    }
+    /// The indices of the neighbors of the cell at index `i`.
+    pub fn neighbors(&self, i: usize) -> &[usize] {
+        &self.neighbors[i]
+    }
+}
+
+/// An automaton on a [`Graph`], keeping a flag per cell rather than a set of
+/// live cells. Much faster than [`Automaton`] when a large share of a
+/// finite topology is live.
+#[derive(Clone, Debug)]
+pub struct DenseAutomaton<C> {
+    graph: Graph<C>,
+    rule: Rule,
+    live: Vec<bool>,
+}
+
+impl<C: Copy + Eq + Hash> DenseAutomaton<C> {
+    /// Cells of `live` outside the graph are ignored.
+    pub fn new(graph: Graph<C>, rule: Rule, live: impl IntoIterator<Item = C>) -> Self {
+        let mut flags = vec![false; graph.cells.len()];
+        for i in live.into_iter().filter_map(|c| graph.index(c)) {
+            flags[i] = true;
+        }
+        DenseAutomaton { graph, rule, live: flags }
+    }
+
+    /// The cells and their neighbors.
+    pub fn graph(&self) -> &Graph<C> {
+        &self.graph
+    }
+    /// The rule the cells follow.
+    pub fn rule(&self) -> &Rule {
+        &self.rule
+    }
+    /// The live cells, in the order of the graph.
+    pub fn live(&self) -> impl Iterator<Item = C> + '_ {
+        self.graph.cells.iter().zip(&self.live).filter(|(_, &l)| l).map(|(&c, _)| c)
+    }
+    /// Whether `cell` is live. Cells outside the graph never are.
+    pub fn is_live(&self, cell: C) -> bool {
+        self.graph.index(cell).is_some_and(|i| self.live[i])
+    }
+    /// Number of live cells.
+    pub fn len(&self) -> usize {
+        self.live.iter().filter(|&&l| l).count()
+    }
+    /// Whether no cell is live.
+    pub fn is_empty(&self) -> bool {
+        !self.live.contains(&true)
+    }
+
+    /// Updates every cell at once.
+    pub fn step(&mut self) {
+        let mut counts = vec![0; self.live.len()];
+        for (i, _) in self.live.iter().enumerate().filter(|(_, &l)| l) {
+            for &n in self.graph.neighbors(i) {
+                counts[n] += 1;
+            }
+        }
+        for (live, count) in self.live.iter_mut().zip(counts) {
+            *live = self.rule.next(*live, count);
+        }
+    }
+
+    /// Like [`Automaton::settle`].
+    pub fn settle(&mut self, limit: usize) -> Option<Settled> {
+        let start = self.live.clone();
+        let (live, settled) = find_cycle(start, limit, |live| {
+            mem::swap(&mut self.live, live);
+            self.step();
+            mem::swap(&mut self.live, live);
+        })?;
+        self.live = live;
+        Some(settled)
+    }
+}
+
+/// The N-dimensional integer lattice, where a cell neighbors the 3^N - 1
+/// cells differing by at most one in every coordinate.
+#[derive(Clone, Debug)]
+pub struct Lattice<const N: usize> {
+    offsets: Vec<[i64; N]>,
+}
+
+impl<const N: usize> Lattice<N> {
+    /// The lattice, with its neighbor offsets worked out.
+    pub fn new() -> Self {
+        let mut offsets = vec![[0; N]];
+        for axis in 0..N {
+            offsets = offsets
+                .into_iter()
+                .flat_map(|o| {
+                    (-1..=1).map(move |d| {
+                        let mut o = o;
+                        o[axis] = d;
+                        o
+                    })
+                })
+                .collect();
+        }
+        offsets.retain(|o| o.iter().any(|&d| d != 0));
+        Lattice { offsets }
+    }
+}
+
+impl<const N: usize> Default for Lattice<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Topology for Lattice<N> {
+    type Cell = [i64; N];
+    type Neighbors = Vec<[i64; N]>;
+
+    fn neighbors(&self, cell: [i64; N]) -> Vec<[i64; N]> {
+        self.offsets
+            .iter()
+            .map(|o| {
+                let mut n = cell;
+                n.iter_mut().zip(o).for_each(|(c, d)| *c += d);
+                n
+            })
+            .collect()
+    }
+}
+
+/// The part of the N-dimensional lattice where every axis from `first` on
+/// is non-negative, standing for the whole lattice when the live cells are
+/// mirror-symmetric in those axes. A cell stands for its
+/// [`multiplicity`](Mirrored::multiplicity) mirror images, and its
+/// neighbors are those of all its images, folded back into the half-space.
+#[derive(Clone, Debug)]
+pub struct Mirrored<const N: usize> {
+    lattice: Lattice<N>,
+    first: usize,
+}
+
+impl<const N: usize> Mirrored<N> {
+    /// Panics if `first` is past the last axis.
+    pub fn new(first: usize) -> Self {
+        assert!(first <= N, "no axis {} in {} dimensions", first, N);
+        Mirrored { lattice: Lattice::new(), first }
+    }
+
+    /// Number of cells of the whole lattice `cell` stands for.
+    pub fn multiplicity(&self, cell: [i64; N]) -> usize {
+        1 << cell[self.first..].iter().filter(|&&c| c != 0).count()
+    }
+}
+
+impl<const N: usize> Topology for Mirrored<N> {
+    type Cell = [i64; N];
+    type Neighbors = Vec<[i64; N]>;
+
+    fn neighbors(&self, cell: [i64; N]) -> Vec<[i64; N]> {
+        // Only images at -1 on a mirrored axis can still reach the half-space.
+        let ones: Vec<_> = (self.first..N).filter(|&i| cell[i] == 1).collect();
+        let mut neighbors = Vec::new();
+        for mask in 0..1 << ones.len() {
+            let mut image = cell;
+            for (bit, &axis) in ones.iter().enumerate() {
+                if mask & 1 << bit != 0 {
+                    image[axis] = -1;
+                }
+            }
+            for o in &self.lattice.offsets {
+                let mut n = image;
+                n.iter_mut().zip(o).for_each(|(c, d)| *c += d);
+                if n[self.first..].iter().all(|&c| c >= 0) {
+                    neighbors.push(n);
+                }
+            }
+        }
+        neighbors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        find_cycle, Automaton, DenseAutomaton, Graph, Lattice, Mirrored, Rule, Settled,
+        Topology,
+    };
+    #[test]
+    fn cycle_detection() {
+        // 0, 1, 2, then 3, 4, 5, 6, 7 over and over.
+        let next = |n: &mut u32| *n = if *n == 7 { 3 } else { *n + 1 };
+        let cycle = Settled::Cycle { start: 3, period: 5 };
+        assert_eq!(find_cycle(0, 8, next), Some((3, cycle)));
+        assert_eq!(find_cycle(0, 7, next), None);
+        assert_eq!(find_cycle(5, 5, next), Some((5, Settled::Cycle { start: 0, period: 5 })));
+        let still = |_: &mut u32| ();
+        assert_eq!(find_cycle(1, 1, still), Some((1, Settled::Stable { steps: 0 })));
+        assert_eq!(find_cycle(1, 0, still), None);
+    }
+    #[test]
+    fn blinker() {
+        let row = [[0, 1], [1, 1], [2, 1]];
+        let mut life = Automaton::new(Lattice::<2>::new(), Rule::life(), row);
+        life.step();
+        let mut live: Vec<_> = life.live().iter().copied().collect();
+        live.sort();
+        assert_eq!(live, [[1, 0], [1, 1], [1, 2]]);
+        life.step();
+        assert!(row.iter().all(|&c| life.is_live(c)));
+        assert_eq!(life.len(), 3);
+        let settled = life.settle(10);
+        assert_eq!(settled, Some(Settled::Cycle { start: 0, period: 2 }));
+        let block = [[0, 0], [0, 1], [1, 0], [1, 1]];
+        let mut life = Automaton::new(Lattice::<2>::new(), Rule::life(), block);
+        assert_eq!(life.settle(10), Some(Settled::Stable { steps: 0 }));
+        let glider = [[1, 0], [2, 1], [0, 2], [1, 2], [2, 2]];
+        let mut life = Automaton::new(Lattice::<2>::new(), Rule::life(), glider);
+        assert_eq!(life.settle(20), None);
+    }
+    /// A ring of cells, each neighboring the next and the previous.
+    struct Ring(Vec<usize>);
+    impl Topology for Ring {
+        type Cell = usize;
+        type Neighbors = [usize; 2];
+        fn neighbors(&self, c: usize) -> [usize; 2] {
+            let n = self.0.len();
+            [(c + n - 1) % n, (c + 1) % n]
+        }
+        fn cells(&self) -> Option<&[usize]> {
+            Some(&self.0)
+        }
+    }
+    #[test]
+    fn finite_birth() {
+        // Births with 0 neighbors only happen on finite topologies.
+        let ring = Ring((0..6).collect());
+        let mut a = Automaton::new(ring, Rule::new([0], []), [0]);
+        a.step();
+        let mut live: Vec<_> = a.live().iter().copied().collect();
+        live.sort();
+        assert_eq!(live, [2, 3, 4]);
+        let graph = Graph::new(a.topology());
+        assert_eq!(graph.neighbors(0), [5, 1]);
+        let mut dense = DenseAutomaton::new(graph, Rule::new([0], []), [0]);
+        dense.step();
+        assert_eq!(dense.live().collect::<Vec<_>>(), [2, 3, 4]);
+        let settled = dense.settle(10);
+        assert_eq!(settled, Some(Settled::Cycle { start: 0, period: 2 }));
+        assert_eq!(Lattice::<4>::new().neighbors([0; 4]).len(), 80);
+    }
+    #[test]
+    fn rule_notation() {
+        assert_eq!("B3/S23".parse(), Ok(Rule::life()));
+        assert_eq!("b36/s32".parse(), Ok(Rule::new([3, 6], [2, 3])));
+        let rule: Rule = "B5-7/S10,12".parse().unwrap();
+        assert_eq!(rule, Rule::new([5, 6, 7], [10, 12]));
+        assert_eq!(rule.to_string(), "B567/S10,12");
+        assert_eq!(Rule::new([], [0]).to_string(), "B/S0");
+        let err = "B3/X23".parse::<Rule>().unwrap_err();
+        assert_eq!((err.column, err.expected.as_str()), (4, "`S`"));
+        let err = "B3x/S23".parse::<Rule>().unwrap_err();
+        assert_eq!((err.column, err.text.as_str()), (3, "x"));
+        assert!("B3S23".parse::<Rule>().is_err());
+        assert!("B03/S23".parse::<Rule>().is_ok());
+        let err = Rule::parse_infinite("B03/S0").unwrap_err();
+        assert_eq!((err.column, err.text.as_str()), (2, "0"));
+        assert_eq!(err.expected, "a count of at least 1");
+        assert_eq!(Rule::parse_infinite("B1-3/S0"), Ok(Rule::new([1, 2, 3], [0])));
+    }
+    #[test]
+    fn mirrored() {
+        // A blinker across the y axis, mirrored in y.
+        let half = Mirrored::<2>::new(1);
+        assert_eq!(half.multiplicity([0, 0]), 1);
+        assert_eq!(half.multiplicity([3, 1]), 2);
+        let mut life = Automaton::new(half, Rule::life(), [[0, 0], [0, 1]]);
+        life.step();
+        let mut live: Vec<_> = life.live().iter().copied().collect();
+        live.sort();
+        assert_eq!(live, [[-1, 0], [0, 0], [1, 0]]);
+        life.step();
+        let half = life.topology();
+        let weight: usize = life.live().iter().map(|&c| half.multiplicity(c)).sum();
+        assert_eq!(weight, 3);
+    }
+}
@@ -0,0 +1,780 @@
+//! Runs, checks, times and visualizes the solutions; see `aoc --help`.
+use std::{
+    any::Any,
+    env, fs,
+    io::{self, BufRead, Read, Write},
+    panic::{self, AssertUnwindSafe},
+    path::Path,
+    process, thread,
+    time::{Duration, Instant},
+};
+
+use aoc_2020::day08::{Cpu, Debugger, Event, Outcome, Trace};
+use aoc_2020::day11::{Game, SeatRules};
+use aoc_2020::day20;
+use aoc_2020::{Answers, DAYS};
+
+const USAGE: &str = "usage: aoc --day <1-25> [--part <1|2>] [--input <path|->]
+       aoc verify [--answers <path>]
+       aoc bench [--day <1-25>] [--runs <n>] [--out <path>]
+       aoc seats [--part <1|2>] [--input <path>] [--delay <ms>]
+                 [--frames <dir> [--format <txt|ppm>] [--scale <n>]]
+       aoc jigsaw [--input <path>] [--out <dir>] [--scale <n>]
+       aoc debug [--input <path>] [--break <index>]... [--watch]
+       aoc trace [--input <path>] [--repair] [--out <path> | --replay <path>]
+       aoc trace --diff <path> <path>
+
+Prints the answer for the given day. Both parts are solved if --part is
+left out. The input is read from input/dayNN unless --input is given, and
+from stdin if the path is '-'.
+
+verify solves every input listed in the answers manifest (answers.txt
+unless --answers is given) and checks the answers against it. Parts of
+each day's own input that have no expected answer are reported as missing.
+
+bench times parsing and each part on every day's own input, or only the
+given day's, taking the median of --runs runs (1 by default). It prints a
+table and writes the timings in nanoseconds as CSV to target/bench.csv
+unless --out is given.
+
+seats animates the day 11 seat layout in the terminal, one round every
+--delay milliseconds (100 by default), under the rules of part 1 or 2, until
+a layout repeats. With --frames it instead writes each round to a numbered
+file in the given directory, as text or as a PPM image with --scale pixels
+per place (4 by default).
+
+jigsaw assembles the day 20 image and draws it, with its sea monsters
+highlighted, to image.svg and image.ppm in the given directory (target/day20
+by default), and the placed tiles with their ids and borders to tiles.svg,
+at --scale units per pixel (8 by default).
+
+debug runs the day 8 program under a debugger taking commands on stdin,
+with breakpoints on the given instruction indices and the accumulator
+watched if --watch is given. Type 'help' at its prompt for the commands.
+
+trace records every step of the day 8 program, or of the program with the
+part 2 fix if --repair is given, until it loops or ends, and writes the
+trace to --out or stdout. With --replay it instead checks that the program
+runs as in a saved trace. --diff shows two saved traces side by side,
+marking the steps that differ.";
+
+const DEBUG_HELP: &str = "commands:
+  s, step [n]     run n instructions (1 by default), ignoring breakpoints
+  c, continue     run until a breakpoint, a watched change, a loop or the end
+  b, break <i>    stop before instruction i
+  d, delete <i>   remove the breakpoint on instruction i
+  w, watch        toggle stopping whenever acc changes
+  p, print        show the next instruction and acc
+  v, visited      list the instructions that have run
+  l, list         show the program, marking the head (>) and breakpoints (*)
+  q, quit         leave the debugger
+An empty line repeats the last command.";
+
+const ANSWERS: &str = "answers.txt";
+const BENCH_OUT: &str = "target/bench.csv";
+const JIGSAW_OUT: &str = "target/day20";
+
+struct Args {
+    day: u8,
+    part: Option<u8>,
+    input: Option<String>,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut day = None;
+    let mut part = None;
+    let mut input = None;
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value for {}", arg));
+        match arg.as_str() {
+            "--day" => {
+                let v = value()?;
+                day = Some(v.parse().map_err(|_| format!("invalid day: {}", v))?);
+            }
+            "--part" => {
+                let v = value()?;
+                part = Some(v.parse().map_err(|_| format!("invalid part: {}", v))?);
+            }
+            "--input" => input = Some(value()?),
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            _ => return Err(format!("unknown argument: {}", arg)),
+        }
+    }
+    let day = day.ok_or("missing --day")?;
+    if !(1..=25).contains(&day) {
+        return Err(format!("invalid day: {}", day));
+    }
+    if let Some(p) = part.filter(|p| !(1..=2).contains(p)) {
+        return Err(format!("invalid part: {}", p));
+    }
+    Ok(Args { day, part, input })
+}
+
+fn parse_verify_args(mut args: impl Iterator<Item = String>) -> Result<String, String> {
+    let mut answers = ANSWERS.to_string();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--answers" => answers = args.next().ok_or(format!("missing value for {}", arg))?,
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            _ => return Err(format!("unknown argument: {}", arg)),
+        }
+    }
+    Ok(answers)
+}
+
+struct BenchArgs {
+    day: Option<u8>,
+    runs: usize,
+    out: String,
+}
+
+fn parse_bench_args(mut args: impl Iterator<Item = String>) -> Result<BenchArgs, String> {
+    let mut bench = BenchArgs {
+        day: None,
+        runs: 1,
+        out: BENCH_OUT.to_string(),
+    };
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value for {}", arg));
+        match arg.as_str() {
+            "--day" => {
+                let v = value()?;
+                let day = v.parse().ok().filter(|d| (1..=25).contains(d));
+                bench.day = Some(day.ok_or(format!("invalid day: {}", v))?);
+            }
+            "--runs" => {
+                let v = value()?;
+                let runs = v.parse().ok().filter(|&n| n > 0);
+                bench.runs = runs.ok_or(format!("invalid number of runs: {}", v))?;
+            }
+            "--out" => bench.out = value()?,
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            _ => return Err(format!("unknown argument: {}", arg)),
+        }
+    }
+    Ok(bench)
+}
+
+struct SeatsArgs {
+    part: u8,
+    input: String,
+    delay: u64,
+    frames: Option<String>,
+    ppm: bool,
+    scale: usize,
+}
+
+fn parse_seats_args(mut args: impl Iterator<Item = String>) -> Result<SeatsArgs, String> {
+    let mut seats = SeatsArgs {
+        part: 1,
+        input: "input/day11".to_string(),
+        delay: 100,
+        frames: None,
+        ppm: false,
+        scale: 4,
+    };
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value for {}", arg));
+        match arg.as_str() {
+            "--part" => {
+                let v = value()?;
+                let part = v.parse().ok().filter(|p| (1..=2).contains(p));
+                seats.part = part.ok_or(format!("invalid part: {}", v))?;
+            }
+            "--input" => seats.input = value()?,
+            "--delay" => {
+                let v = value()?;
+                seats.delay = v.parse().map_err(|_| format!("invalid delay: {}", v))?;
+            }
+            "--frames" => seats.frames = Some(value()?),
+            "--format" => {
+                seats.ppm = match value()?.as_str() {
+                    "txt" => false,
+                    "ppm" => true,
+                    v => return Err(format!("invalid format: {}", v)),
+                }
+            }
+            "--scale" => {
+                let v = value()?;
+                let scale = v.parse().ok().filter(|&n| n > 0);
+                seats.scale = scale.ok_or(format!("invalid scale: {}", v))?;
+            }
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            _ => return Err(format!("unknown argument: {}", arg)),
+        }
+    }
+    Ok(seats)
+}
+
+struct JigsawArgs {
+    input: String,
+    out: String,
+    scale: usize,
+}
+
+fn parse_jigsaw_args(mut args: impl Iterator<Item = String>) -> Result<JigsawArgs, String> {
+    let mut jigsaw = JigsawArgs {
+        input: "input/day20".to_string(),
+        out: JIGSAW_OUT.to_string(),
+        scale: 8,
+    };
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value for {}", arg));
+        match arg.as_str() {
+            "--input" => jigsaw.input = value()?,
+            "--out" => jigsaw.out = value()?,
+            "--scale" => {
+                let v = value()?;
+                let scale = v.parse().ok().filter(|&n| n > 0);
+                jigsaw.scale = scale.ok_or(format!("invalid scale: {}", v))?;
+            }
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            _ => return Err(format!("unknown argument: {}", arg)),
+        }
+    }
+    Ok(jigsaw)
+}
+
+struct DebugArgs {
+    input: String,
+    breakpoints: Vec<usize>,
+    watch: bool,
+}
+
+fn parse_debug_args(mut args: impl Iterator<Item = String>) -> Result<DebugArgs, String> {
+    let mut debug = DebugArgs {
+        input: "input/day08".to_string(),
+        breakpoints: Vec::new(),
+        watch: false,
+    };
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value for {}", arg));
+        match arg.as_str() {
+            "--input" => debug.input = value()?,
+            "--break" => {
+                let v = value()?;
+                let index = v.parse().map_err(|_| format!("invalid index: {}", v))?;
+                debug.breakpoints.push(index);
+            }
+            "--watch" => debug.watch = true,
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            _ => return Err(format!("unknown argument: {}", arg)),
+        }
+    }
+    Ok(debug)
+}
+
+struct TraceArgs {
+    input: String,
+    repair: bool,
+    out: Option<String>,
+    replay: Option<String>,
+    diff: Option<(String, String)>,
+}
+
+fn parse_trace_args(mut args: impl Iterator<Item = String>) -> Result<TraceArgs, String> {
+    let mut trace = TraceArgs {
+        input: "input/day08".to_string(),
+        repair: false,
+        out: None,
+        replay: None,
+        diff: None,
+    };
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value for {}", arg));
+        match arg.as_str() {
+            "--input" => trace.input = value()?,
+            "--repair" => trace.repair = true,
+            "--out" => trace.out = Some(value()?),
+            "--replay" => trace.replay = Some(value()?),
+            "--diff" => trace.diff = Some((value()?, value()?)),
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            _ => return Err(format!("unknown argument: {}", arg)),
+        }
+    }
+    if trace.out.is_some() && trace.replay.is_some() {
+        return Err("--out and --replay cannot be combined".to_string());
+    }
+    Ok(trace)
+}
+
+/// Returns the name of the input, for error messages, and its contents.
+fn read_input(args: &Args) -> Result<(String, String), String> {
+    match args.input.as_deref() {
+        Some("-") => {
+            let mut input = String::new();
+            io::stdin()
+                .read_to_string(&mut input)
+                .map_err(|e| format!("failed to read stdin: {}", e))?;
+            Ok(("stdin".to_string(), input))
+        }
+        path => {
+            let path = path
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("input/day{:02}", args.day));
+            let input =
+                fs::read_to_string(&path).map_err(|e| format!("failed to read {}: {}", path, e))?;
+            Ok((path, input))
+        }
+    }
+}
+
+/// The message a panic was raised with.
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    match payload.downcast_ref::<&str>() {
+        Some(s) => s,
+        None => payload.downcast_ref::<String>().map_or("unknown panic", String::as_str),
+    }
+}
+
+/// Checks every answer in the manifest at `path`, printing one line per part.
+/// Parts that panic are reported as failed instead of stopping the run; the
+/// default panic hook is silenced meanwhile, as each message is printed.
+fn verify(path: &str) -> Result<(), String> {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let result = verify_answers(path);
+    panic::set_hook(hook);
+    result
+}
+
+fn verify_answers(path: &str) -> Result<(), String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("failed to read {}: {}", path, e))?;
+    let answers = Answers::parse(&text).map_err(|e| format!("{}: {}", path, e))?;
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    for day in DAYS.iter() {
+        let own = format!("input/day{:02}", day.day);
+        let mut inputs = answers.inputs(day.day);
+        if !inputs.contains(&own.as_str()) {
+            inputs.insert(0, &own);
+        }
+        for input in inputs {
+            let expected = [1, 2].map(|part| answers.get(day.day, input, part));
+            let puzzle = fs::read_to_string(input)
+                .map_err(|e| e.to_string())
+                .and_then(|text| day.parse(&text).map_err(|e| e.to_string()));
+            let puzzle = match puzzle {
+                Ok(puzzle) => puzzle,
+                Err(e) => {
+                    let parts = expected.iter().filter(|e| e.is_some()).count();
+                    if parts == 0 {
+                        println!("day {:2} {}: no answers, {}", day.day, input, e);
+                        missing += 1;
+                    } else {
+                        println!("day {:2} {}: FAILED, {}", day.day, input, e);
+                        failed += parts;
+                    }
+                    continue;
+                }
+            };
+            for (part, expected) in (1..=2).zip(expected) {
+                // Some inputs only suit one part, such as day 14's examples.
+                if expected.is_none() && input != own {
+                    continue;
+                }
+                let label = format!("day {:2} part {} {}", day.day, part, input);
+                let got = panic::catch_unwind(AssertUnwindSafe(|| match part {
+                    1 => Some(puzzle.part1()),
+                    _ => puzzle.part2(),
+                }));
+                let got = match got {
+                    Ok(got) => got,
+                    Err(payload) => {
+                        println!("{}: FAILED, panicked: {}", label, panic_message(&*payload));
+                        failed += 1;
+                        continue;
+                    }
+                };
+                match (expected, got) {
+                    (Some(expected), Some(got)) if expected == got => {
+                        println!("{}: ok", label);
+                        passed += 1;
+                    }
+                    (Some(expected), got) => {
+                        let got = got.unwrap_or_else(|| "no solver".to_string());
+                        println!("{}: FAILED, expected {}, got {}", label, expected, got);
+                        failed += 1;
+                    }
+                    (None, Some(got)) => {
+                        println!("{}: missing, got {}", label, got);
+                        missing += 1;
+                    }
+                    (None, None) => {}
+                }
+            }
+        }
+    }
+    println!("{} passed, {} failed, {} missing", passed, failed, missing);
+    if failed > 0 {
+        return Err(format!("{} answers failed", failed));
+    }
+    Ok(())
+}
+
+/// Runs `f` `runs` times, returning the median time and the last result.
+fn time<T>(runs: usize, mut f: impl FnMut() -> T) -> (Duration, T) {
+    let mut times = Vec::with_capacity(runs);
+    let mut result = None;
+    for _ in 0..runs {
+        let start = Instant::now();
+        result = Some(f());
+        times.push(start.elapsed());
+    }
+    times.sort();
+    (times[runs / 2], result.unwrap())
+}
+
+fn format_time(time: Option<Duration>) -> String {
+    match time.map(|t| t.as_secs_f64()) {
+        None => "-".to_string(),
+        Some(s) if s < 1e-3 => format!("{:.1}µs", s * 1e6),
+        Some(s) if s < 1.0 => format!("{:.2}ms", s * 1e3),
+        Some(s) => format!("{:.2}s", s),
+    }
+}
+
+/// Times every day, printing a table and writing the timings as CSV.
+fn bench(args: BenchArgs) -> Result<(), String> {
+    let mut csv = String::from("day,parse_ns,part1_ns,part2_ns\n");
+    let row = |day: &str, times: [Option<Duration>; 4]| {
+        let [parse, part1, part2, total] = times.map(format_time);
+        println!(
+            "{:>3} {:>10} {:>10} {:>10} {:>10}",
+            day, parse, part1, part2, total
+        );
+    };
+    println!(
+        "{:>3} {:>10} {:>10} {:>10} {:>10}",
+        "day", "parse", "part 1", "part 2", "total"
+    );
+    let mut sum = Duration::ZERO;
+    for day in DAYS.iter().filter(|d| args.day.is_none_or(|n| n == d.day)) {
+        let path = format!("input/day{:02}", day.day);
+        let input =
+            fs::read_to_string(&path).map_err(|e| format!("failed to read {}: {}", path, e))?;
+        let (parse, puzzle) = time(args.runs, || day.parse(&input));
+        let puzzle = puzzle.map_err(|e| format!("{}: {}", path, e))?;
+        let (part1, _) = time(args.runs, || puzzle.part1());
+        let (part2, answer) = time(args.runs, || puzzle.part2());
+        let part2 = answer.map(|_| part2);
+        let total = parse + part1 + part2.unwrap_or_default();
+        sum += total;
+        row(
+            &day.day.to_string(),
+            [Some(parse), Some(part1), part2, Some(total)],
+        );
+        let part2 = part2.map_or(String::new(), |t| t.as_nanos().to_string());
+        csv += &format!(
+            "{},{},{},{}\n",
+            day.day,
+            parse.as_nanos(),
+            part1.as_nanos(),
+            part2
+        );
+    }
+    row("all", [None, None, None, Some(sum)]);
+    fs::write(&args.out, csv).map_err(|e| format!("failed to write {}: {}", args.out, e))?;
+    eprintln!("wrote {}", args.out);
+    Ok(())
+}
+
+/// Shows or writes every round of the seat layout until it repeats.
+fn seats(args: SeatsArgs) -> Result<(), String> {
+    let input = fs::read_to_string(&args.input)
+        .map_err(|e| format!("failed to read {}: {}", args.input, e))?;
+    let game = Game::new(&input).map_err(|e| format!("{}: {}", args.input, e))?;
+    let rules = match args.part {
+        1 => SeatRules::part1(),
+        _ => SeatRules::part2(),
+    };
+    if let Some(dir) = &args.frames {
+        fs::create_dir_all(dir).map_err(|e| format!("failed to create {}: {}", dir, e))?;
+    }
+    let mut rounds = 0;
+    for (round, frame) in game.generations(&rules).enumerate() {
+        match &args.frames {
+            Some(dir) => {
+                let (ext, data) = match args.ppm {
+                    true => ("ppm", frame.to_ppm(args.scale)),
+                    false => ("txt", format!("{}\n", frame).into_bytes()),
+                };
+                let path = Path::new(dir).join(format!("{:05}.{}", round, ext));
+                fs::write(&path, data)
+                    .map_err(|e| format!("failed to write {}: {}", path.display(), e))?;
+            }
+            None => {
+                // Clear the screen and move to the top left before drawing.
+                println!("\x1b[2J\x1b[H{}", frame);
+                println!("round {}: {} occupied", round, frame.occupied_seats());
+                thread::sleep(Duration::from_millis(args.delay));
+            }
+        }
+        rounds = round;
+    }
+    eprintln!("round {} repeats an earlier layout", rounds + 1);
+    Ok(())
+}
+
+/// Draws the assembled day 20 image and its tiles.
+fn jigsaw(args: JigsawArgs) -> Result<(), String> {
+    let input = fs::read_to_string(&args.input)
+        .map_err(|e| format!("failed to read {}: {}", args.input, e))?;
+    let tiles = day20::parse_tiles(&input).map_err(|e| format!("{}: {}", args.input, e))?;
+    let layout = day20::assemble(tiles).map_err(|e| format!("{}: {}", args.input, e))?;
+    let image = day20::join_tiles(&layout);
+    let monsters = day20::sea_monsters(&image);
+    let files = [
+        ("image.svg", day20::image_svg(&image, &monsters, args.scale).into_bytes()),
+        ("image.ppm", day20::image_ppm(&image, &monsters, args.scale)),
+        ("tiles.svg", day20::tiles_svg(&layout, &monsters, args.scale).into_bytes()),
+    ];
+    fs::create_dir_all(&args.out).map_err(|e| format!("failed to create {}: {}", args.out, e))?;
+    for (name, data) in files {
+        let path = Path::new(&args.out).join(name);
+        fs::write(&path, data).map_err(|e| format!("failed to write {}: {}", path.display(), e))?;
+        eprintln!("wrote {}", path.display());
+    }
+    Ok(())
+}
+
+/// Prints the next instruction and the accumulator.
+fn print_head(dbg: &Debugger) {
+    let acc = dbg.cpu().acc();
+    match dbg.current() {
+        Some((i, op)) => println!("{:>5}: {:<10} acc = {}", i, op, acc),
+        None => match dbg.cpu().out_of_bounds() {
+            Some(i) => println!("jumped out of the program to {}, acc = {}", i, acc),
+            None => println!("halted at {}, acc = {}", dbg.cpu().head(), acc),
+        },
+    }
+}
+
+/// Says how a traced run ended, on stderr so the trace can be piped.
+fn print_outcome(outcome: Outcome) {
+    match outcome {
+        Outcome::Halted { acc } => eprintln!("halted, acc = {}", acc),
+        Outcome::Looped { index, acc } => {
+            eprintln!("instruction {} runs again: the program loops, acc = {}", index, acc)
+        }
+        Outcome::OutOfBounds { index, acc } => {
+            eprintln!("jumped out of the program to {}, acc = {}", index, acc)
+        }
+    }
+}
+
+fn print_event(event: Event) {
+    match event {
+        // The head that follows says where the program ended.
+        Event::Step | Event::Halted | Event::OutOfBounds(_) => {}
+        Event::Breakpoint(i) => println!("breakpoint at {}", i),
+        Event::Watch { old, new } => println!("acc changed from {} to {}", old, new),
+        Event::Loop(i) => println!("instruction {} runs again: the program loops", i),
+    }
+}
+
+/// Runs one debugger command. Returns false to quit.
+fn debug_command(dbg: &mut Debugger, line: &str) -> Result<bool, String> {
+    let mut words = line.split_whitespace();
+    let command = words.next().unwrap_or("");
+    let mut index = || {
+        let v = words.next().ok_or(format!("{} needs an instruction index", command))?;
+        v.parse::<usize>().map_err(|_| format!("invalid index: {}", v))
+    };
+    match command {
+        "s" | "step" => {
+            let n = match words.next() {
+                Some(v) => v.parse().map_err(|_| format!("invalid count: {}", v))?,
+                None => 1,
+            };
+            for _ in 0..n {
+                let event = dbg.step();
+                print_event(event);
+                if event != Event::Step {
+                    break;
+                }
+            }
+            print_head(dbg);
+        }
+        "c" | "continue" => {
+            print_event(dbg.cont());
+            print_head(dbg);
+        }
+        "b" | "break" => {
+            let i = index()?;
+            if i >= dbg.cpu().instructions().len() {
+                return Err(format!("no instruction {}", i));
+            }
+            dbg.set_breakpoint(i);
+        }
+        "d" | "delete" => {
+            let i = index()?;
+            if !dbg.clear_breakpoint(i) {
+                return Err(format!("no breakpoint at {}", i));
+            }
+        }
+        "w" | "watch" => {
+            let watch = !dbg.is_watching_acc();
+            dbg.watch_acc(watch);
+            println!("watching acc: {}", if watch { "on" } else { "off" });
+        }
+        "p" | "print" => print_head(dbg),
+        "v" | "visited" => {
+            let visited: Vec<_> = dbg.visited().map(|i| i.to_string()).collect();
+            println!("{} visited: {}", visited.len(), visited.join(" "));
+        }
+        "l" | "list" => {
+            for (i, op) in dbg.cpu().instructions().iter().enumerate() {
+                let head = if i == dbg.cpu().head() { '>' } else { ' ' };
+                let brk = if dbg.breakpoints().contains(&i) { '*' } else { ' ' };
+                let runs = match dbg.visits(i) {
+                    0 => String::new(),
+                    n => format!("ran {}x", n),
+                };
+                let line = format!("{}{}{:>5}: {:<10} {}", head, brk, i, op, runs);
+                println!("{}", line.trim_end());
+            }
+        }
+        "q" | "quit" => return Ok(false),
+        "h" | "help" => println!("{}", DEBUG_HELP),
+        _ => return Err(format!("unknown command: {}; try 'help'", command)),
+    }
+    Ok(true)
+}
+
+/// Debugs the day 8 program with commands read from stdin.
+fn debug(args: DebugArgs) -> Result<(), String> {
+    let input = fs::read_to_string(&args.input)
+        .map_err(|e| format!("failed to read {}: {}", args.input, e))?;
+    let cpu = Cpu::new(&input).map_err(|e| format!("{}: {}", args.input, e))?;
+    let mut dbg = Debugger::new(cpu);
+    let len = dbg.cpu().instructions().len();
+    for &i in &args.breakpoints {
+        if i >= len {
+            return Err(format!("{}: no instruction {}", args.input, i));
+        }
+        dbg.set_breakpoint(i);
+    }
+    dbg.watch_acc(args.watch);
+    print_head(&dbg);
+    let mut last = String::new();
+    let mut lines = io::stdin().lock().lines();
+    loop {
+        print!("(dbg) ");
+        io::stdout().flush().map_err(|e| e.to_string())?;
+        let line = match lines.next() {
+            Some(line) => line.map_err(|e| format!("failed to read stdin: {}", e))?,
+            None => return Ok(()),
+        };
+        if !line.trim().is_empty() {
+            last = line;
+        }
+        match debug_command(&mut dbg, &last) {
+            Ok(true) => {}
+            Ok(false) => return Ok(()),
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+}
+
+fn read_trace(path: &str) -> Result<Trace, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("failed to read {}: {}", path, e))?;
+    Trace::parse(&text).map_err(|e| format!("{}: {}", path, e))
+}
+
+/// Records, replays or compares traces of the day 8 program.
+fn trace(args: TraceArgs) -> Result<(), String> {
+    if let Some((a, b)) = &args.diff {
+        let (a, b) = (read_trace(a)?, read_trace(b)?);
+        print!("{}", a.side_by_side(&b));
+        match a.first_divergence(&b) {
+            Some(n) => println!("traces differ from step {}", n),
+            None => println!("traces are the same"),
+        }
+        return Ok(());
+    }
+    let input = fs::read_to_string(&args.input)
+        .map_err(|e| format!("failed to read {}: {}", args.input, e))?;
+    let mut cpu = Cpu::new(&input).map_err(|e| format!("{}: {}", args.input, e))?;
+    if args.repair {
+        let (index, fixed) = cpu.repair().ok_or("no single instruction fix halts")?;
+        eprintln!("swapped instruction {}", index);
+        cpu = fixed;
+    }
+    if let Some(path) = &args.replay {
+        let trace = read_trace(path)?;
+        return match trace.replay(cpu) {
+            Ok(_) => {
+                println!("replayed {} steps", trace.0.len());
+                Ok(())
+            }
+            Err(n) => Err(format!("step {} differs from {}: {}", n, path, trace.0[n])),
+        };
+    }
+    cpu.record();
+    print_outcome(cpu.run_until_loop_or_halt());
+    let trace = cpu.take_trace().unwrap_or_default();
+    match &args.out {
+        Some(path) => {
+            fs::write(path, trace.to_string())
+                .map_err(|e| format!("failed to write {}: {}", path, e))?;
+            eprintln!("wrote {} steps to {}", trace.0.len(), path);
+        }
+        None => print!("{}", trace),
+    }
+    Ok(())
+}
+
+fn run() -> Result<(), String> {
+    let mut args = env::args().skip(1).peekable();
+    match args.peek().map(String::as_str) {
+        Some("verify") => {
+            args.next();
+            return verify(&parse_verify_args(args)?);
+        }
+        Some("bench") => {
+            args.next();
+            return bench(parse_bench_args(args)?);
+        }
+        Some("seats") => {
+            args.next();
+            return seats(parse_seats_args(args)?);
+        }
+        Some("jigsaw") => {
+            args.next();
+            return jigsaw(parse_jigsaw_args(args)?);
+        }
+        Some("debug") => {
+            args.next();
+            return debug(parse_debug_args(args)?);
+        }
+        Some("trace") => {
+            args.next();
+            return trace(parse_trace_args(args)?);
+        }
+        _ => {}
+    }
+    let args = parse_args(args)?;
+    let (name, input) = read_input(&args)?;
+    let day = aoc_2020::day(args.day).unwrap();
+    let puzzle = day.parse(&input).map_err(|e| format!("{}: {}", name, e))?;
+    if args.part != Some(2) {
+        println!("day {} part 1: {}", args.day, puzzle.part1());
+    }
+    if args.part != Some(1) {
+        match puzzle.part2() {
+            Some(ans) => println!("day {} part 2: {}", args.day, ans),
+            None if args.part.is_none() => {
+                eprintln!("day {} part 2: no solver", args.day)
+            }
+            None => return Err(format!("no solver for day {} part 2", args.day)),
+        }
+    }
+    Ok(())
+}
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
@@ -1,6 +1,10 @@
+//! Day 1: Report Repair.
+use crate::parse::{number, ParseError};
+use crate::solution::Solution;
 use std::collections::HashSet;
 
-fn find_sum_part1(sum: u32, terms: &[u32]) -> Option<(u32, u32)> {
+/// Finds two terms adding up to `sum`.
+pub fn find_sum_part1(sum: u32, terms: &[u32]) -> Option<(u32, u32)> {
     let set: HashSet<u32> = terms.iter().cloned().collect();
     for &first in terms {
         let remainder = sum - first;
@@ -11,7 +15,8 @@ fn find_sum_part1(sum: u32, terms: &[u32]) -> Option<(u32, u32)> {
     None
 }
 
-fn find_sum_part2(sum: u32, mut terms: Vec<u32>) -> Option<(u32, u32, u32)> {
+/// Finds three terms adding up to `sum`.
+pub fn find_sum_part2(sum: u32, mut terms: Vec<u32>) -> Option<(u32, u32, u32)> {
     terms.sort_unstable();
     for (i, &first) in terms.iter().enumerate() {
         if first >= sum {
@@ -36,6 +41,27 @@ fn find_sum_part2(sum: u32, mut terms: Vec<u32>) -> Option<(u32, u32, u32)> {
     None
 }
 
+/// Solver for day 1.
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Input = Vec<u32>;
+    type Output1 = u32;
+    type Output2 = u32;
+
+    fn parse(input: &str) -> Result<Vec<u32>, ParseError> {
+        input.lines().enumerate().map(|(i, s)| number(i, s, s)).collect()
+    }
+    fn part1(terms: &Vec<u32>) -> u32 {
+        let (a, b) = find_sum_part1(2020, terms).unwrap();
+        a * b
+    }
+    fn part2(terms: &Vec<u32>) -> u32 {
+        let (a, b, c) = find_sum_part2(2020, terms.clone()).unwrap();
+        a * b * c
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs;
@@ -46,7 +72,7 @@ mod tests {
     fn example() {
         let inp = fs::read_to_string("input/example01").unwrap();
         let terms: Vec<_> =
-            inp.lines().into_iter().map(|s| s.parse().unwrap()).collect();
+            inp.lines().map(|s| s.parse().unwrap()).collect();
         let res = find_sum_part1(2020, &terms);
         assert_eq!(res, Some((1721, 299)));
     }
@@ -54,7 +80,7 @@ mod tests {
     fn part1() {
         let inp = fs::read_to_string("input/day01").unwrap();
         let terms: Vec<_> =
-            inp.lines().into_iter().map(|s| s.parse().unwrap()).collect();
+            inp.lines().map(|s| s.parse().unwrap()).collect();
         let res = find_sum_part1(2020, &terms);
         if let Some((a, b)) = res {
             assert_eq!(a * b, 786811);
@@ -66,7 +92,7 @@ mod tests {
     fn part2() {
         let inp = fs::read_to_string("input/day01").unwrap();
         let terms =
-            inp.lines().into_iter().map(|s| s.parse().unwrap()).collect();
+            inp.lines().map(|s| s.parse().unwrap()).collect();
         let res = find_sum_part2(2020, terms);
         if let Some((a, b, c)) = res {
             assert_eq!(a * b * c, 199068980);
@@ -1,6 +1,9 @@
-use scan_fmt::scan_fmt;
+//! Day 2: Password Philosophy.
+use crate::parse::{number, ParseError};
+use crate::solution::Solution;
 
-struct Password {
+/// A password together with the policy it was set under.
+pub struct Password {
     min: usize,
     max: usize,
     c: char,
@@ -8,25 +11,65 @@ struct Password {
 }
 
 impl Password {
-    fn verify(&self) -> bool {
+    /// Checks the old policy: the character count is within min and max.
+    pub fn verify(&self) -> bool {
         let count = self.pass.iter().filter(|&&c| self.c == c).count();
         self.min <= count && count <= self.max
     }
-    fn verify2(&self) -> bool {
-        (self.pass[self.min - 1] == self.c)
-            ^ (self.pass[self.max - 1] == self.c)
+    /// Checks the new policy: exactly one of the two positions holds the
+    /// character.
+    pub fn verify2(&self) -> bool {
+        (self.pass.get(self.min - 1) == Some(&self.c))
+            ^ (self.pass.get(self.max - 1) == Some(&self.c))
     }
 }
-fn parse(input: &str) -> Vec<Password> {
+/// Parses `<min>-<max> <char>: <password>` lines, pointing errors at the
+/// field that is wrong.
+pub fn parse(input: &str) -> Result<Vec<Password>, ParseError> {
     let mut result = Vec::new();
-    for line in input.lines() {
-        let (min, max, c, pass) =
-            scan_fmt!(line, "{}-{} {}: {}", usize, usize, char, String)
-                .unwrap();
+    for (i, line) in input.lines().enumerate() {
+        let missing = |text: &str, expected| {
+            ParseError::at(i, line, &text[text.len()..], expected)
+        };
+        let (policy, pass) = line.split_once(": ").ok_or_else(|| missing(line, "`: `"))?;
+        let (range, letter) = policy.split_once(' ').ok_or_else(|| missing(policy, "` `"))?;
+        let (min, max) = range.split_once('-').ok_or_else(|| missing(range, "`-`"))?;
+        let position = |text: &str| match number(i, line, text)? {
+            0 => Err(ParseError::at(i, line, text, "a position starting at 1")),
+            n => Ok(n),
+        };
+        let (min, max) = (position(min)?, position(max)?);
+        let mut chars = letter.chars();
+        let c = match (chars.next(), chars.next()) {
+            (Some(c), None) => c,
+            _ => return Err(ParseError::at(i, line, letter, "a single character")),
+        };
+        if pass.is_empty() || pass.contains(char::is_whitespace) {
+            return Err(ParseError::at(i, line, pass, "a password"));
+        }
         let pass = pass.chars().collect();
         result.push(Password { min, max, c, pass });
     }
-    result
+    Ok(result)
+}
+
+/// Solver for day 2.
+pub struct Day02;
+
+impl Solution for Day02 {
+    type Input = Vec<Password>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Vec<Password>, ParseError> {
+        parse(input)
+    }
+    fn part1(passwords: &Vec<Password>) -> usize {
+        passwords.iter().filter(|p| p.verify()).count()
+    }
+    fn part2(passwords: &Vec<Password>) -> usize {
+        passwords.iter().filter(|p| p.verify2()).count()
+    }
 }
 
 #[cfg(test)]
@@ -36,22 +79,35 @@ mod tests {
     #[test]
     fn example() {
         let inp = fs::read_to_string("input/example02").unwrap();
-        let passwords = parse(&inp);
+        let passwords = parse(&inp).unwrap();
         let valid = passwords.iter().filter(|p| p.verify()).count();
         assert_eq!(valid, 2);
     }
     #[test]
     fn part1() {
         let inp = fs::read_to_string("input/day02").unwrap();
-        let passwords = parse(&inp);
+        let passwords = parse(&inp).unwrap();
         let valid = passwords.iter().filter(|p| p.verify()).count();
         assert_eq!(valid, 600);
     }
     #[test]
     fn part2() {
         let inp = fs::read_to_string("input/day02").unwrap();
-        let passwords = parse(&inp);
+        let passwords = parse(&inp).unwrap();
         let valid = passwords.iter().filter(|p| p.verify2()).count();
         assert_eq!(valid, 245);
     }
+    #[test]
+    fn bad_line() {
+        let err = parse("1-3 a: abcde\n1-x b: cdefg").err().unwrap();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 3, "x"));
+        let err = parse("0-3 a: abcde").err().unwrap();
+        assert_eq!((err.column, err.expected.as_str()), (1, "a position starting at 1"));
+        let err = parse("1-3 ab: abcde").err().unwrap();
+        assert_eq!((err.column, err.text.as_str()), (5, "ab"));
+        let err = parse("1-3 a: ab cde").err().unwrap();
+        assert_eq!((err.column, err.text.as_str()), (8, "ab cde"));
+        let err = parse("1-3 a abcde").err().unwrap();
+        assert_eq!((err.column, err.expected.as_str()), (12, "`: `"));
+    }
 }
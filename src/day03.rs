@@ -1,23 +1,23 @@
-use std::collections::HashMap;
+//! Day 3: Toboggan Trajectory.
+use crate::grid::Grid;
+use crate::parse::ParseError;
+use crate::solution::Solution;
 
 enum Tile {
     Tree,
     Ground,
 }
-struct Map {
-    tiles: HashMap<(usize, usize), Tile>,
-    height: usize,
-    width: usize,
-}
+/// The repeating map of open squares and trees.
+pub struct Map(Grid<Tile>);
 
 impl Map {
-    fn trees_hit(&self, (dir_x, dir_y): (usize, usize)) -> u32 {
+    /// Counts the trees hit going down the slope `(right, down)`.
+    pub fn trees_hit(&self, (dir_x, dir_y): (usize, usize)) -> u32 {
         let mut hit = 0;
         let mut x = dir_x;
         let mut y = dir_y;
-        while y < self.height {
-            let pos = (x % self.width, y);
-            match self.tiles[&pos] {
+        while y < self.0.height() {
+            match self.0.get_wrapping((x, y)) {
                 Tile::Tree => hit += 1,
                 Tile::Ground => {}
             }
@@ -29,21 +29,36 @@ impl Map {
     }
 }
 
-fn parse(input: &str) -> Map {
-    let height = input.lines().count();
-    let width = input.lines().next().unwrap().chars().count();
-    let mut tiles = HashMap::with_capacity(height * width);
-    for (i, line) in input.lines().enumerate() {
-        for (j, c) in line.chars().enumerate() {
-            match c {
-                '.' => tiles.insert((j, i), Tile::Ground),
-                '#' => tiles.insert((j, i), Tile::Tree),
-                _ => panic!("unknown symbol"),
-            };
-        }
+/// Parses the map of `.` open squares and `#` trees.
+pub fn parse(input: &str) -> Result<Map, ParseError> {
+    let tiles = Grid::parse(input, "`.` or `#`", |c| match c {
+        '.' => Some(Tile::Ground),
+        '#' => Some(Tile::Tree),
+        _ => None,
+    })?;
+    Ok(Map(tiles))
+}
+
+/// Solver for day 3.
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Input = Map;
+    type Output1 = u32;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Map, ParseError> {
+        parse(input)
+    }
+    fn part1(map: &Map) -> u32 {
+        map.trees_hit((3, 1))
+    }
+    fn part2(map: &Map) -> usize {
+        let slopes = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
+        slopes.iter().map(|dir| map.trees_hit(*dir) as usize).product()
     }
-    Map { tiles, height, width }
 }
+
 #[cfg(test)]
 mod tests {
     use std::fs;
@@ -52,22 +67,22 @@ mod tests {
     #[test]
     fn example() {
         let inp = fs::read_to_string("input/example03").unwrap();
-        let map = parse(&inp);
+        let map = parse(&inp).unwrap();
         let ans = map.trees_hit((3, 1));
         assert_eq!(ans, 7);
     }
     #[test]
     fn part1() {
         let inp = fs::read_to_string("input/day03").unwrap();
-        let map = parse(&inp);
+        let map = parse(&inp).unwrap();
         let ans = map.trees_hit((3, 1));
         assert_eq!(ans, 280);
     }
     #[test]
     fn part2() {
         let inp = fs::read_to_string("input/day03").unwrap();
-        let map = parse(&inp);
-        let slopes = vec![(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
+        let map = parse(&inp).unwrap();
+        let slopes = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
         let ans: usize =
             slopes.iter().map(|dir| map.trees_hit(*dir) as usize).product();
         assert_eq!(ans, 4355551200);
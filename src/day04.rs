@@ -1,6 +1,8 @@
+//! Day 4: Passport Processing.
+use crate::parse::{sections, ParseError};
+use crate::solution::Solution;
 use std::collections::HashMap;
 
-const OPTIONAL: [&str; 1] = ["cid"];
 const REQUIRED: [&str; 7] = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"];
 const EYE_COLOR: [&str; 7] = ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
 
@@ -14,21 +16,17 @@ fn valid_field(k: &str, v: &str) -> bool {
         "iyr" => digits_in_range(2010, 2020, v),
         "eyr" => digits_in_range(2020, 2030, v),
         "hgt" => {
-            let (num, unit) = v.split_at(v.len() - 2);
+            let (num, unit) = v.split_at(v.len().saturating_sub(2));
             match unit {
                 "cm" => digits_in_range(150, 193, num),
                 "in" => digits_in_range(59, 76, num),
                 _ => false,
             }
         }
-        "hcl" => {
-            let (a, b) = v.split_at(1);
-            a == "#"
-                && b.chars().all(|c| {
-                    ('0'..='9').contains(&c) || ('a'..='f').contains(&c)
-                })
-        }
-        "ecl" => EYE_COLOR.iter().any(|&color| color == v),
+        "hcl" => v.strip_prefix('#').is_some_and(|b| {
+            b.chars().all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c))
+        }),
+        "ecl" => EYE_COLOR.contains(&v),
         "pid" => {
             v.chars().all(|c| c.is_ascii_digit());
             v.chars().count() == 9
@@ -36,39 +34,60 @@ fn valid_field(k: &str, v: &str) -> bool {
         _ => false,
     }
 }
-struct Passport(HashMap<String, String>);
+/// Passport fields by key.
+pub struct Passport(HashMap<String, String>);
 
 impl Passport {
-    fn is_valid(&self) -> bool {
+    /// All required fields are present.
+    pub fn is_valid(&self) -> bool {
         REQUIRED.iter().all(|&s| self.0.contains_key(s))
     }
 
-    fn is_valid2(&self) -> bool {
+    /// All required fields are present and valid.
+    pub fn is_valid2(&self) -> bool {
         REQUIRED.iter().all(|&s| {
             self.0.contains_key(s) && valid_field(s, self.0.get(s).unwrap())
         })
     }
 }
 
-fn parse(input: &str) -> Vec<Passport> {
+/// Parses the passports, separated by blank lines.
+pub fn parse(input: &str) -> Result<Vec<Passport>, ParseError> {
     let mut result = Vec::new();
-    let mut pass: HashMap<String, String> = HashMap::new();
-    for line in input.lines() {
-        if line.is_empty() {
-            result.push(Passport(pass.clone()));
-            pass = HashMap::new();
-        }
-        let split = line.split_whitespace();
-        for keyval in split {
-            let kv_split: Vec<&str> = keyval.splitn(2, ':').collect();
-            pass.insert(kv_split[0].to_string(), kv_split[1].to_string());
+    for (first, section) in sections(input) {
+        let mut pass: HashMap<String, String> = HashMap::new();
+        for (i, line) in section.lines().enumerate() {
+            for keyval in line.split_whitespace() {
+                let (k, v) = keyval.split_once(':').ok_or_else(|| {
+                    ParseError::at(first + i, line, keyval, "`<key>:<value>`")
+                })?;
+                pass.insert(k.to_string(), v.to_string());
+            }
         }
+        result.push(Passport(pass));
+    }
+    Ok(result)
+}
+
+/// Solver for day 4.
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Input = Vec<Passport>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Vec<Passport>, ParseError> {
+        parse(input)
     }
-    if !pass.is_empty() {
-        result.push(Passport(pass))
+    fn part1(passports: &Vec<Passport>) -> usize {
+        passports.iter().filter(|p| p.is_valid()).count()
+    }
+    fn part2(passports: &Vec<Passport>) -> usize {
+        passports.iter().filter(|p| p.is_valid2()).count()
     }
-    result
 }
+
 #[cfg(test)]
 mod tests {
     use std::fs;
@@ -77,14 +96,14 @@ mod tests {
     #[test]
     fn example() {
         let inp = fs::read_to_string("input/example04").unwrap();
-        let passports = parse(&inp);
+        let passports = parse(&inp).unwrap();
         let valid = passports.iter().filter(|p| p.is_valid()).count();
         assert_eq!(valid, 2);
     }
     #[test]
     fn part1() {
         let inp = fs::read_to_string("input/day04").unwrap();
-        let passports = parse(&inp);
+        let passports = parse(&inp).unwrap();
         let valid = passports.iter().filter(|p| p.is_valid()).count();
         assert_eq!(valid, 204);
         // 203 too low;
@@ -92,7 +111,7 @@ mod tests {
     #[test]
     fn part2() {
         let inp = fs::read_to_string("input/day04").unwrap();
-        let passports = parse(&inp);
+        let passports = parse(&inp).unwrap();
         let valid = passports.iter().filter(|p| p.is_valid2()).count();
         assert_eq!(valid, 179);
     }
@@ -1,49 +1,89 @@
-struct Seat {
+//! Day 5: Binary Boarding.
+use crate::parse::ParseError;
+use crate::solution::Solution;
+
+/// A seat on the plane.
+pub struct Seat {
     row: usize,
     col: usize,
 }
 impl Seat {
     // TODO: Replace for-loops with some bitshift magic
-    fn parse(s: &str) -> Self {
-        let mut chars = s.chars();
+    /// Decodes a boarding pass such as `FBFBBFFRLR`. Errors are reported
+    /// on the first line.
+    pub fn parse(s: &str) -> Result<Self, ParseError> {
+        if s.chars().count() != 10 {
+            return Err(ParseError::at(0, s, s, "10 letters"));
+        }
+        let bad = |j, c: char, expected| {
+            Err(ParseError::new(0, j, &c.to_string(), expected))
+        };
+        let mut chars = s.chars().enumerate();
         let mut row = 0;
         let mut n = 64;
-        for c in chars.by_ref().take(7) {
+        for (j, c) in chars.by_ref().take(7) {
             match c {
                 'F' => (),
                 'B' => row += n,
-                _ => panic!(),
+                _ => return bad(j, c, "`F` or `B`"),
             }
             n /= 2;
         }
         let mut col = 0;
         let mut n = 4;
-        for c in chars.take(3) {
+        for (j, c) in chars.take(3) {
             match c {
                 'L' => (),
                 'R' => col += n,
-                _ => panic!(),
+                _ => return bad(j, c, "`L` or `R`"),
             }
             n /= 2;
         }
-        Seat { row, col }
+        Ok(Seat { row, col })
     }
-    fn get_id(&self) -> usize {
+    /// The seat ID, the row times 8 plus the column.
+    pub fn get_id(&self) -> usize {
         self.row * 8 + self.col
     }
 }
 
-fn parse(input: &str) -> Vec<Seat> {
-    input.lines().map(|l| Seat::parse(l)).collect()
+/// Parses one boarding pass per line.
+pub fn parse(input: &str) -> Result<Vec<Seat>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, l)| Seat::parse(l).map_err(|e| e.offset(i)))
+        .collect()
 }
 
-fn find_missing_seat(seats: &[Seat]) -> Option<usize> {
+/// Finds the free seat whose neighbouring ids are both taken.
+pub fn find_missing_seat(seats: &[Seat]) -> Option<usize> {
     let mut ids_present = vec![false; 128 * 8];
     seats.iter().for_each(|s| ids_present[s.get_id()] = true);
     let triple_pos =
         ids_present.windows(3).position(|w| w == [true, false, true]);
     triple_pos.map(|tp| tp + 1)
 }
+
+/// Solver for day 5.
+pub struct Day05;
+
+impl Solution for Day05 {
+    type Input = Vec<Seat>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Vec<Seat>, ParseError> {
+        parse(input)
+    }
+    fn part1(seats: &Vec<Seat>) -> usize {
+        seats.iter().map(|s| s.get_id()).max().unwrap()
+    }
+    fn part2(seats: &Vec<Seat>) -> usize {
+        find_missing_seat(seats).unwrap()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs;
@@ -52,20 +92,22 @@ mod tests {
     #[test]
     fn example() {
         let inp = "FBFBBFFRLR";
-        let s = Seat::parse(inp);
+        let s = Seat::parse(inp).unwrap();
         assert_eq!(s.get_id(), 357);
+        let err = parse("FBFBBFFRLR\nFBFBXFFRLR").err().unwrap();
+        assert_eq!((err.line, err.column), (2, 5));
     }
     #[test]
     fn part1() {
         let inp = fs::read_to_string("input/day05").unwrap();
-        let seats = parse(&inp);
+        let seats = parse(&inp).unwrap();
         let ans = seats.iter().map(|s| s.get_id()).max();
         assert_eq!(ans, Some(904));
     }
     #[test]
     fn part2() {
         let inp = fs::read_to_string("input/day05").unwrap();
-        let seats = parse(&inp);
+        let seats = parse(&inp).unwrap();
         let ans = find_missing_seat(&seats);
         assert_eq!(ans, Some(669));
     }
@@ -1,26 +1,37 @@
+//! Day 6: Custom Customs.
+use crate::parse::{sections, ParseError};
+use crate::solution::Solution;
 use std::collections::HashSet;
 
-fn parse_forms(input: &str) -> Vec<HashSet<char>> {
-    let mut result = Vec::new();
-    let mut form = HashSet::new();
-    for line in input.lines() {
-        if line.is_empty() {
-            result.push(form.clone());
-            form = HashSet::new();
-        }
-        line.chars().for_each(|c| {
-            form.insert(c);
-        })
-    }
-    if !form.is_empty() {
-        result.push(form)
-    }
-    result
+/// Parses each group into the set of questions anyone answered.
+pub fn parse_forms(input: &str) -> Vec<HashSet<char>> {
+    sections(input)
+        .into_iter()
+        .map(|(_, form)| form.lines().flat_map(str::chars).collect())
+        .collect()
 }
 
-fn parse_forms2(input: &str) -> Vec<Vec<&str>> {
-    let lines: Vec<_> = input.lines().collect();
-    lines.split(|l| l.is_empty()).map(|form| form.to_vec()).collect()
+/// Parses each group into the answers of each person.
+pub fn parse_forms2(input: &str) -> Vec<Vec<&str>> {
+    sections(input)
+        .into_iter()
+        .map(|(_, form)| form.lines().collect())
+        .collect()
+}
+
+/// Checks that every answer is a question from `a` to `z`.
+fn check_answers(input: &str) -> Result<(), ParseError> {
+    for (first, form) in sections(input) {
+        for (i, line) in form.lines().enumerate() {
+            if let Some((j, c)) =
+                line.chars().enumerate().find(|(_, c)| !c.is_ascii_lowercase())
+            {
+                let err = ParseError::new(i, j, &c.to_string(), "`a` to `z`");
+                return Err(err.offset(first));
+            }
+        }
+    }
+    Ok(())
 }
 
 fn to_bitset(input: &str) -> usize {
@@ -32,7 +43,18 @@ fn to_bitset(input: &str) -> usize {
     bitset
 }
 
-fn count_answers(forms: Vec<Vec<&str>>) -> u32 {
+/// Parses each group into the answers of each person, one bit per question.
+pub fn parse_groups(input: &str) -> Result<Vec<Vec<usize>>, ParseError> {
+    check_answers(input)?;
+    let groups = parse_forms2(input)
+        .into_iter()
+        .map(|form| form.into_iter().map(to_bitset).collect())
+        .collect();
+    Ok(groups)
+}
+
+/// Sums, over the groups, the questions everyone in the group answered.
+pub fn count_answers(forms: Vec<Vec<&str>>) -> u32 {
     let mut ans = 0;
     for form in forms {
         let (first, rest) = form.split_first().unwrap();
@@ -42,11 +64,34 @@ fn count_answers(forms: Vec<Vec<&str>>) -> u32 {
     }
     ans
 }
+
+/// Solver for day 6.
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Input = Vec<Vec<usize>>;
+    type Output1 = usize;
+    type Output2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_groups(input)
+    }
+    fn part1(groups: &Self::Input) -> usize {
+        let anyone = |group: &Vec<usize>| group.iter().fold(0, |acc, p| acc | p);
+        groups.iter().map(|g| anyone(g).count_ones() as usize).sum()
+    }
+    fn part2(groups: &Self::Input) -> u32 {
+        let everyone = |group: &Vec<usize>| group.iter().fold(!0, |acc, p| acc & p);
+        groups.iter().map(|g| everyone(g).count_ones()).sum()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs;
 
-    use super::{count_answers, parse_forms, parse_forms2};
+    use super::{check_answers, count_answers, parse_forms, parse_forms2, parse_groups, Day06};
+    use crate::solution::Solution;
     #[test]
     fn example() {
         let input = fs::read_to_string("input/example06").unwrap();
@@ -75,4 +120,17 @@ mod tests {
         let ans = count_answers(forms);
         assert_eq!(ans, 3402);
     }
+    #[test]
+    fn bad_answer() {
+        let err = check_answers("abc\n\na1").unwrap_err();
+        assert_eq!((err.line, err.column), (3, 2));
+        assert!(parse_groups("abc\n\na1").is_err());
+    }
+    #[test]
+    fn groups() {
+        let input = fs::read_to_string("input/example06").unwrap();
+        let groups = Day06::parse(&input).unwrap();
+        assert_eq!(groups[1], [0b1, 0b10, 0b100]);
+        assert_eq!((Day06::part1(&groups), Day06::part2(&groups)), (11, 6));
+    }
 }
@@ -1,42 +1,72 @@
+//! Day 7: Handy Haversacks.
+use crate::parse::{number, ParseError};
+use crate::solution::Solution;
 use std::collections::{HashMap, HashSet};
 
-fn parse_bag_info(input: &str) -> (&str, Vec<(usize, &str, &str)>) {
+/// A bag's color and the counts and colors of the bags it contains.
+type BagInfo<'a> = (&'a str, Vec<(usize, &'a str)>);
+
+fn parse_bag_info(input: &str) -> Result<BagInfo<'_>, ParseError> {
     //"light red bags contain 1 bright white bag, 2 muted yellow bags.";
-    let mut split = input.split(" bags contain ");
-    let container = split.next().unwrap();
-    let content = split.next().unwrap().strip_suffix('.').unwrap();
+    let err = |text, expected| ParseError::at(0, input, text, expected);
+    let (container, content) = input
+        .split_once(" bags contain ")
+        .ok_or_else(|| err(input, "`<color> bags contain <bags>.`"))?;
+    let content = content
+        .strip_suffix('.')
+        .ok_or_else(|| err(&input[input.len()..], "`.`"))?;
     if content == "no other bags" {
-        return (container, vec![]);
+        return Ok((container, vec![]));
     }
-    let content: Vec<(usize, &str, &str)> = content
+    let content = content
         .split(", ")
         .map(|s| {
-            let mut split = s.split_whitespace();
-            let n = split.next().unwrap().parse().unwrap();
-            (n, split.next().unwrap(), split.next().unwrap())
+            let (n, bag) = s
+                .split_once(' ')
+                .ok_or_else(|| err(s, "`<count> <color> bags`"))?;
+            let n = number(0, input, n)?;
+            let color = bag
+                .strip_suffix(" bags")
+                .or_else(|| bag.strip_suffix(" bag"))
+                .ok_or_else(|| err(bag, "`<color> bags`"))?;
+            Ok((n, color))
         })
-        .collect();
-    (container, content)
+        .collect::<Result<_, ParseError>>()?;
+    Ok((container, content))
 }
 
-type Rules = HashMap<String, Vec<(String, usize)>>;
-fn parse_rules(input: &str) -> Rules {
-    input
+/// The bags, and the count of each, directly inside each bag.
+pub type Rules = HashMap<String, Vec<(String, usize)>>;
+/// Parses one rule per line.
+pub fn parse_rules(input: &str) -> Result<Rules, ParseError> {
+    let infos = input
         .lines()
-        .map(|line| parse_bag_info(line))
-        .map(|info| {
-            let key = info.0.to_string();
-            let vals = info
-                .1
+        .enumerate()
+        .map(|(i, line)| parse_bag_info(line).map_err(|e| e.offset(i)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let rules: Rules = infos
+        .iter()
+        .map(|(container, content)| {
+            let vals = content
                 .iter()
-                .map(|(n, s1, s2)| (s1.to_string() + " " + s2, *n))
+                .map(|(n, color)| (color.to_string(), *n))
                 .collect();
-            (key, vals)
+            (container.to_string(), vals)
         })
-        .collect()
+        .collect();
+    for ((i, line), (_, content)) in input.lines().enumerate().zip(&infos) {
+        if let Some((_, color)) =
+            content.iter().find(|(_, color)| !rules.contains_key(*color))
+        {
+            return Err(ParseError::at(i, line, color, "a bag with a rule"));
+        }
+    }
+    Ok(rules)
 }
 
-fn bag_contains_gold(bag: &str, memo: &mut HashSet<String>, rules: &Rules) -> bool {
+/// Checks whether `bag` can eventually hold a bag in `memo`, adding `bag`
+/// to `memo` if it can. Seed `memo` with "shiny gold".
+pub fn bag_contains_gold(bag: &str, memo: &mut HashSet<String>, rules: &Rules) -> bool {
     if memo.contains(bag) {
         return true;
     }
@@ -49,7 +79,8 @@ fn bag_contains_gold(bag: &str, memo: &mut HashSet<String>, rules: &Rules) -> bo
     false
 }
 
-fn bags_inside(bag: &str, memo: &mut HashMap<String, usize>, rules: &Rules) -> usize {
+/// Counts the bags inside `bag`.
+pub fn bags_inside(bag: &str, memo: &mut HashMap<String, usize>, rules: &Rules) -> usize {
     if let Some(n) = memo.get(bag) {
         return *n;
     }
@@ -60,6 +91,33 @@ fn bags_inside(bag: &str, memo: &mut HashMap<String, usize>, rules: &Rules) -> u
     memo.insert(bag.to_string(), bag_count);
     bag_count
 }
+
+/// Solver for day 7.
+pub struct Day07;
+
+impl Solution for Day07 {
+    type Input = Rules;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Rules, ParseError> {
+        parse_rules(input)
+    }
+    fn part1(rules: &Rules) -> usize {
+        let mut memo = HashSet::new();
+        memo.insert("shiny gold".to_string());
+        rules
+            .keys()
+            .filter(|k| *k != "shiny gold")
+            .filter(|k| bag_contains_gold(k, &mut memo, rules))
+            .count()
+    }
+    fn part2(rules: &Rules) -> usize {
+        let mut memo = HashMap::new();
+        bags_inside("shiny gold", &mut memo, rules)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{
@@ -71,13 +129,17 @@ mod tests {
     #[test]
     fn example() {
         let input = "light red bags contain 1 bright white bag, 2 muted yellow bags.";
-        let parsed = parse_bag_info(input);
+        let parsed = parse_bag_info(input).unwrap();
         dbg!(parsed);
+        let input = "light red bags contain 1 bright white bag.";
+        let err = parse_rules(input).unwrap_err();
+        assert_eq!((err.line, err.column), (1, 26));
+        assert_eq!(err.text, "bright white");
     }
     #[test]
     fn part1() {
         let input = fs::read_to_string("input/day07").unwrap();
-        let rules = parse_rules(&input);
+        let rules = parse_rules(&input).unwrap();
         let mut memo = HashSet::new();
         memo.insert("shiny gold".to_string());
         let ans = rules
@@ -89,7 +151,7 @@ mod tests {
     #[test]
     fn part2() {
         let input = fs::read_to_string("input/day07").unwrap();
-        let rules = parse_rules(&input);
+        let rules = parse_rules(&input).unwrap();
         let mut memo = HashMap::new();
         let ans = bags_inside("shiny gold", &mut memo, &rules);
         dbg!(ans);
@@ -1,34 +1,88 @@
-#[derive(Clone)]
-enum Op {
+//! Day 8: Handheld Halting.
+use crate::parse::{number, ParseError};
+use crate::solution::Solution;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display};
+
+/// A handheld console instruction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Op {
+    /// Jump by the argument.
     Jmp(i32),
+    /// Add the argument to the accumulator.
     Acc(i32),
+    /// Do nothing.
     Nop(i32),
 }
 
+/// Prints the instruction as in the program, like `jmp -3`.
+impl Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            Op::Jmp(n) => format!("jmp {:+}", n),
+            Op::Acc(n) => format!("acc {:+}", n),
+            Op::Nop(n) => format!("nop {:+}", n),
+        };
+        f.pad(&text)
+    }
+}
+
+/// Parses `text`, a slice of the zero-based `line`, as an instruction.
+fn parse_op(line: usize, line_text: &str, text: &str) -> Result<Op, ParseError> {
+    let (op, arg) = text.split_once(' ').ok_or_else(|| {
+        ParseError::at(line, line_text, text, "`<op> <argument>`")
+    })?;
+    let arg = number(line, line_text, arg)?;
+    match op {
+        "jmp" => Ok(Op::Jmp(arg)),
+        "acc" => Ok(Op::Acc(arg)),
+        "nop" => Ok(Op::Nop(arg)),
+        _ => Err(ParseError::at(line, line_text, op, "`jmp`, `acc` or `nop`")),
+    }
+}
+
+/// The handheld console: a program, an instruction pointer and the
+/// accumulator.
 #[derive(Clone)]
-struct Cpu {
+pub struct Cpu {
     instructions: Vec<Op>,
     head: usize,
     acc: i32,
+    /// The steps run since recording started, if it has.
+    trace: Option<Vec<TraceStep>>,
 }
 
 impl Cpu {
-    fn new(input: &str) -> Self {
+    /// Parses the program, one instruction per line, ready to run from the start.
+    pub fn new(input: &str) -> Result<Self, ParseError> {
         let instructions = input
             .lines()
-            .map(|s| {
-                let mut split = s.split_whitespace();
-                match split.next().unwrap() {
-                    "jmp" => Op::Jmp(split.next().unwrap().parse().unwrap()),
-                    "acc" => Op::Acc(split.next().unwrap().parse().unwrap()),
-                    "nop" => Op::Nop(split.next().unwrap().parse().unwrap()),
-                    _ => panic!(),
-                }
-            })
-            .collect();
-        Self { instructions, head: 0, acc: 0 }
+            .enumerate()
+            .map(|(i, s)| parse_op(i, s, s))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { instructions, head: 0, acc: 0, trace: None })
+    }
+    /// Executes one instruction. Returns false if the program has
+    /// terminated.
+    pub fn run(&mut self) -> bool {
+        let (index, acc_before) = (self.head, self.acc);
+        let ran = self.execute();
+        if let Some(trace) = self.trace.as_mut().filter(|_| ran) {
+            let op = self.instructions[index].clone();
+            trace.push(TraceStep { index, op, acc_before, acc_after: self.acc });
+        }
+        ran
     }
-    fn run(&mut self) -> bool {
+    /// Starts recording every step run from now on, dropping any earlier
+    /// recording.
+    pub fn record(&mut self) {
+        self.trace = Some(Vec::new());
+    }
+    /// Stops recording and returns what was recorded.
+    pub fn take_trace(&mut self) -> Option<Trace> {
+        self.trace.take().map(Trace)
+    }
+    fn execute(&mut self) -> bool {
         match self.instructions.get(self.head) {
             Some(Op::Jmp(i)) => {
                 self.head = (self.head as i32 + i) as usize;
@@ -46,71 +100,520 @@ impl Cpu {
             None => false,
         }
     }
+    /// The accumulator.
+    pub fn acc(&self) -> i32 {
+        self.acc
+    }
+    /// Index of the next instruction.
+    pub fn head(&self) -> usize {
+        self.head
+    }
+    /// The program.
+    pub fn instructions(&self) -> &[Op] {
+        &self.instructions
+    }
+
+    /// Where a jump took the head, if outside the program and not just
+    /// past its end.
+    pub fn out_of_bounds(&self) -> Option<isize> {
+        // Jumps before the start wrap the head to a huge index.
+        Some(self.head as isize).filter(|_| self.head > self.instructions.len())
+    }
+
+    /// Index of the instruction run after the one at `index`, which may be
+    /// outside the program.
+    fn next(&self, index: usize) -> isize {
+        match self.instructions[index] {
+            Op::Jmp(n) => index as isize + n as isize,
+            Op::Acc(_) | Op::Nop(_) => index as isize + 1,
+        }
+    }
+
+    /// Runs from the current state until the program ends or an
+    /// instruction is about to run a second time.
+    pub fn run_until_loop_or_halt(&mut self) -> Outcome {
+        let mut seen = vec![false; self.instructions.len()];
+        loop {
+            match seen.get_mut(self.head) {
+                Some(true) => {
+                    return Outcome::Looped { index: self.head, acc: self.acc }
+                }
+                Some(seen) => *seen = true,
+                None => {
+                    return match self.out_of_bounds() {
+                        Some(index) => Outcome::OutOfBounds { index, acc: self.acc },
+                        None => Outcome::Halted { acc: self.acc },
+                    }
+                }
+            }
+            self.run();
+        }
+    }
+
+    /// Finds the one `jmp` or `nop` to swap for the other to make the
+    /// program halt, returning its index and the fixed program, reset to
+    /// the start. None if the program already halts or no swap helps.
+    ///
+    /// Works in linear time: instructions from which the unchanged program
+    /// halts are found by walking the control flow backwards from the end,
+    /// and then the first instruction on the looping path whose swapped
+    /// successor is among them is the fix.
+    pub fn repair(&self) -> Option<(usize, Cpu)> {
+        let len = self.instructions.len();
+        let mut preds = vec![Vec::new(); len + 1];
+        for i in 0..len {
+            let next = self.next(i);
+            if (0..=len as isize).contains(&next) {
+                preds[next as usize].push(i);
+            }
+        }
+        let mut halts = vec![false; len + 1];
+        halts[len] = true;
+        let mut stack = vec![len];
+        while let Some(i) = stack.pop() {
+            for &p in &preds[i] {
+                if !halts[p] {
+                    halts[p] = true;
+                    stack.push(p);
+                }
+            }
+        }
+        if halts[0] {
+            return None;
+        }
+        let mut seen = vec![false; len];
+        let mut head = 0;
+        while !seen[head] {
+            seen[head] = true;
+            let swapped = match self.instructions[head] {
+                Op::Jmp(n) => Some((Op::Nop(n), head as isize + 1)),
+                Op::Nop(n) => Some((Op::Jmp(n), head as isize + n as isize)),
+                Op::Acc(_) => None,
+            };
+            let halting = |&(_, next): &(Op, isize)| {
+                next >= 0 && halts.get(next as usize) == Some(&true)
+            };
+            if let Some((op, _)) = swapped.filter(halting) {
+                let mut fixed = self.clone();
+                fixed.instructions[head] = op;
+                fixed.head = 0;
+                fixed.acc = 0;
+                if fixed.trace.is_some() {
+                    fixed.record();
+                }
+                return Some((head, fixed));
+            }
+            let next = self.next(head);
+            if !(0..len as isize).contains(&next) {
+                return None;
+            }
+            head = next as usize;
+        }
+        None
+    }
+}
+
+/// One recorded instruction run.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceStep {
+    /// Index of the instruction.
+    pub index: usize,
+    /// The instruction.
+    pub op: Op,
+    /// The accumulator before the instruction ran.
+    pub acc_before: i32,
+    /// The accumulator after it ran.
+    pub acc_after: i32,
+}
+
+/// Prints the step as `<index> <acc before> <acc after> <op>`.
+impl Display for TraceStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (index, before, after) = (self.index, self.acc_before, self.acc_after);
+        write!(f, "{} {} {} {}", index, before, after, self.op)
+    }
+}
+
+/// The steps a [`Cpu`] ran while recording. Saved as text with one
+/// [`TraceStep`] per line.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Trace(pub Vec<TraceStep>);
+
+impl Trace {
+    /// Parses a trace as saved by its `Display` form.
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let steps = input
+            .lines()
+            .enumerate()
+            .map(|(i, l)| {
+                let mut fields = l.splitn(4, ' ');
+                let mut field = || {
+                    let expected = "`<index> <acc before> <acc after> <op>`";
+                    let end = &l[l.len()..];
+                    fields.next().ok_or_else(|| ParseError::at(i, l, end, expected))
+                };
+                let (index, before, after, op) = (field()?, field()?, field()?, field()?);
+                Ok(TraceStep {
+                    index: number(i, l, index)?,
+                    acc_before: number(i, l, before)?,
+                    acc_after: number(i, l, after)?,
+                    op: parse_op(i, l, op)?,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Trace(steps))
+    }
+
+    /// The number of the first step that differs from `other`'s, counting
+    /// a step only one of the traces has. None if they are the same.
+    pub fn first_divergence(&self, other: &Trace) -> Option<usize> {
+        let pairs = self.0.iter().zip(&other.0);
+        let same = pairs.take_while(|(a, b)| a == b).count();
+        (same < self.0.len().max(other.0.len())).then_some(same)
+    }
+
+    /// Shows the two traces in columns, one step per line, marking the
+    /// steps that differ with `*`.
+    pub fn side_by_side(&self, other: &Trace) -> String {
+        let column = |step: Option<&TraceStep>| match step {
+            Some(s) => {
+                let (before, after) = (s.acc_before, s.acc_after);
+                format!("{:>5}: {:<9} {:>6} -> {:<6}", s.index, s.op, before, after)
+            }
+            None => String::new(),
+        };
+        let mut text = String::new();
+        for n in 0..self.0.len().max(other.0.len()) {
+            let (a, b) = (self.0.get(n), other.0.get(n));
+            let mark = if a == b { ' ' } else { '*' };
+            let line = format!("{:>6} {} {:<33} {}", n, mark, column(a), column(b));
+            text += line.trim_end();
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Runs `cpu` for as many steps as the trace has, checking that it
+    /// runs the same instructions with the same results. Returns the cpu
+    /// afterwards, or the number of the first step that differs.
+    pub fn replay(&self, mut cpu: Cpu) -> Result<Cpu, usize> {
+        cpu.record();
+        for (n, step) in self.0.iter().enumerate() {
+            cpu.run();
+            let ran = cpu.trace.as_mut().and_then(|t| t.pop());
+            if ran.as_ref() != Some(step) {
+                cpu.trace = None;
+                return Err(n);
+            }
+        }
+        cpu.trace = None;
+        Ok(cpu)
+    }
+}
+
+/// Prints one step per line.
+impl Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|step| writeln!(f, "{}", step))
+    }
+}
+
+/// How a program run by [`Cpu::run_until_loop_or_halt`] ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// The head moved just past the last instruction.
+    Halted {
+        /// The accumulator at the end.
+        acc: i32,
+    },
+    /// The instruction at `index` was about to run a second time.
+    Looped {
+        /// The instruction about to run again.
+        index: usize,
+        /// The accumulator before it runs again.
+        acc: i32,
+    },
+    /// A jump went to `index`, outside the program and not just past it.
+    OutOfBounds {
+        /// Where the jump went.
+        index: isize,
+        /// The accumulator at the jump.
+        acc: i32,
+    },
+}
+
+/// Why the debugger stopped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Event {
+    /// One instruction ran and nothing else happened.
+    Step,
+    /// The next instruction has a breakpoint.
+    Breakpoint(usize),
+    /// The accumulator changed while watched.
+    Watch {
+        /// The accumulator before the step.
+        old: i32,
+        /// The accumulator after it.
+        new: i32,
+    },
+    /// The next instruction already ran as many times as the most run
+    /// one, so the program loops forever. Reported once per time round.
+    Loop(usize),
+    /// The head is just past the last instruction: the program terminated.
+    Halted,
+    /// A jump went to this index, outside the program.
+    OutOfBounds(isize),
+}
+
+/// Runs a [`Cpu`] under control, with breakpoints on instruction indices
+/// and an optional watch on the accumulator, keeping track of how often
+/// each instruction has run.
+#[derive(Clone)]
+pub struct Debugger {
+    cpu: Cpu,
+    breakpoints: BTreeSet<usize>,
+    watch_acc: bool,
+    visits: BTreeMap<usize, usize>,
+    /// Times round a loop after which to report the next one.
+    laps: usize,
+}
+
+impl Debugger {
+    /// A debugger about to run `cpu`, with no breakpoints and no watch.
+    pub fn new(cpu: Cpu) -> Self {
+        Debugger {
+            cpu,
+            breakpoints: BTreeSet::new(),
+            watch_acc: false,
+            visits: BTreeMap::new(),
+            laps: 0,
+        }
+    }
+
+    /// The debugged program and its state.
+    pub fn cpu(&self) -> &Cpu {
+        &self.cpu
+    }
+    /// The index and instruction about to run, if the program has not
+    /// terminated.
+    pub fn current(&self) -> Option<(usize, &Op)> {
+        let head = self.cpu.head;
+        self.cpu.instructions.get(head).map(|op| (head, op))
+    }
+    /// The indices of the instructions that have run, in order.
+    pub fn visited(&self) -> impl Iterator<Item = usize> + '_ {
+        self.visits.keys().copied()
+    }
+    /// How many times the instruction at `index` has run.
+    pub fn visits(&self, index: usize) -> usize {
+        self.visits.get(&index).copied().unwrap_or(0)
+    }
+
+    /// Sets a breakpoint. Returns false if there already was one.
+    pub fn set_breakpoint(&mut self, index: usize) -> bool {
+        self.breakpoints.insert(index)
+    }
+    /// Removes a breakpoint. Returns false if there was none.
+    pub fn clear_breakpoint(&mut self, index: usize) -> bool {
+        self.breakpoints.remove(&index)
+    }
+    /// The indices with a breakpoint.
+    pub fn breakpoints(&self) -> &BTreeSet<usize> {
+        &self.breakpoints
+    }
+    /// Whether to stop whenever the accumulator changes.
+    pub fn watch_acc(&mut self, watch: bool) {
+        self.watch_acc = watch;
+    }
+    /// Whether the debugger stops when the accumulator changes.
+    pub fn is_watching_acc(&self) -> bool {
+        self.watch_acc
+    }
+
+    /// Runs one instruction, ignoring breakpoints.
+    pub fn step(&mut self) -> Event {
+        let (head, old) = (self.cpu.head, self.cpu.acc);
+        if !self.cpu.run() {
+            return self.cpu.out_of_bounds().map_or(Event::Halted, Event::OutOfBounds);
+        }
+        *self.visits.entry(head).or_default() += 1;
+        let new = self.cpu.acc;
+        if self.watch_acc && new != old {
+            Event::Watch { old, new }
+        } else {
+            Event::Step
+        }
+    }
+
+    /// Runs until a breakpoint, a watched change, a [`Loop`](Event::Loop)
+    /// or the end of the program. Always runs at least one instruction, so
+    /// continuing from a breakpoint moves past it.
+    pub fn cont(&mut self) -> Event {
+        loop {
+            let event = self.step();
+            if event != Event::Step {
+                return event;
+            }
+            let head = self.cpu.head;
+            if self.breakpoints.contains(&head) {
+                return Event::Breakpoint(head);
+            }
+            if self.visits(head) > self.laps {
+                self.laps += 1;
+                return Event::Loop(head);
+            }
+        }
+    }
+}
+
+/// Solver for day 8.
+pub struct Day08;
+
+impl Solution for Day08 {
+    type Input = Cpu;
+    type Output1 = i32;
+    type Output2 = i32;
+
+    fn parse(input: &str) -> Result<Cpu, ParseError> {
+        Cpu::new(input)
+    }
+    fn part1(cpu: &Cpu) -> i32 {
+        match cpu.clone().run_until_loop_or_halt() {
+            Outcome::Looped { acc, .. } => acc,
+            outcome => panic!("program does not loop: {:?}", outcome),
+        }
+    }
+    fn part2(cpu: &Cpu) -> i32 {
+        let (_, mut fixed) = cpu.repair().expect("no single instruction fix halts");
+        match fixed.run_until_loop_or_halt() {
+            Outcome::Halted { acc } => acc,
+            outcome => unreachable!("fixed program does not halt: {:?}", outcome),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::{collections::HashSet, fs};
+    use std::fs;
 
-    use super::{Cpu, Op};
+    use super::{Cpu, Debugger, Event, Op, Outcome, Trace, TraceStep};
     #[test]
     fn example() {
         let input = fs::read_to_string("input/example08").unwrap();
-        let mut cpu = Cpu::new(&input);
-        let mut ins_done = HashSet::new();
-        ins_done.insert(0);
-        while cpu.run() {
-            let head = cpu.head;
-            if ins_done.contains(&head) {
-                break;
-            } else {
-                ins_done.insert(head);
-            }
-        }
-        dbg!(cpu.acc);
+        let cpu = Cpu::new(&input).unwrap();
+        let outcome = cpu.clone().run_until_loop_or_halt();
+        assert_eq!(outcome, Outcome::Looped { index: 1, acc: 5 });
+        let (index, mut fixed) = cpu.repair().unwrap();
+        assert_eq!(index, 7);
+        assert_eq!(fixed.run_until_loop_or_halt(), Outcome::Halted { acc: 8 });
+        assert!(fixed.repair().is_none());
     }
     #[test]
     fn part1() {
         let input = fs::read_to_string("input/day08").unwrap();
-        let mut cpu = Cpu::new(&input);
-        let mut ins_done = HashSet::new();
-        ins_done.insert(0);
-        while cpu.run() {
-            let head = cpu.head;
-            if ins_done.contains(&head) {
-                break;
-            } else {
-                ins_done.insert(head);
-            }
-        }
-        dbg!(cpu.acc);
+        let mut cpu = Cpu::new(&input).unwrap();
+        let outcome = cpu.run_until_loop_or_halt();
+        assert!(matches!(outcome, Outcome::Looped { acc: 1134, .. }));
     }
     #[test]
     fn part2() {
+        // The repair agrees with trying every swap.
         let input = fs::read_to_string("input/day08").unwrap();
-        let cpu = Cpu::new(&input);
-        'outer: for i in 0..cpu.instructions.len() {
-            if let Op::Acc(_) = cpu.instructions[i] {
-                continue;
-            }
-            let mut cpu_fix = cpu.clone();
-            cpu_fix.instructions[i] = match cpu_fix.instructions[i] {
-                Op::Jmp(n) => Op::Nop(n),
-                Op::Acc(_) => {
-                    unreachable!()
+        let cpu = Cpu::new(&input).unwrap();
+        let halting: Vec<_> = (0..cpu.instructions.len())
+            .filter_map(|i| {
+                let mut fixed = cpu.clone();
+                fixed.instructions[i] = match fixed.instructions[i] {
+                    Op::Jmp(n) => Op::Nop(n),
+                    Op::Acc(_) => return None,
+                    Op::Nop(n) => Op::Jmp(n),
+                };
+                match fixed.run_until_loop_or_halt() {
+                    Outcome::Halted { acc } => Some((i, acc)),
+                    _ => None,
                 }
-                Op::Nop(n) => Op::Jmp(n),
-            };
-            let mut ins_done = HashSet::new();
-            ins_done.insert(0);
-            while cpu_fix.run() {
-                if ins_done.contains(&cpu_fix.head) {
-                    continue 'outer;
-                } else {
-                    ins_done.insert(cpu_fix.head);
-                }
-            }
-            println!("answer part2: {}", cpu_fix.acc);
-        }
+            })
+            .collect();
+        let (index, mut fixed) = cpu.repair().unwrap();
+        let Outcome::Halted { acc } = fixed.run_until_loop_or_halt() else {
+            panic!("repaired program does not halt")
+        };
+        assert_eq!(halting, [(index, acc)]);
+        assert_eq!(acc, 1205);
+    }
+    #[test]
+    fn out_of_bounds() {
+        let mut cpu = Cpu::new("acc +1\njmp -2").unwrap();
+        let outcome = cpu.run_until_loop_or_halt();
+        assert_eq!(outcome, Outcome::OutOfBounds { index: -1, acc: 1 });
+        let mut cpu = Cpu::new("nop +5\njmp +3").unwrap();
+        let outcome = cpu.run_until_loop_or_halt();
+        assert_eq!(outcome, Outcome::OutOfBounds { index: 4, acc: 0 });
+        assert!(Cpu::new("jmp +0\njmp -1").unwrap().repair().is_none());
+        let (index, _) = Cpu::new("jmp +0").unwrap().repair().unwrap();
+        assert_eq!(index, 0);
+    }
+    #[test]
+    fn trace() {
+        let input = fs::read_to_string("input/example08").unwrap();
+        let cpu = Cpu::new(&input).unwrap();
+        let mut original = cpu.clone();
+        original.record();
+        original.run_until_loop_or_halt();
+        let original = original.take_trace().unwrap();
+        assert_eq!(original.0.len(), 7);
+        let step = TraceStep { index: 1, op: Op::Acc(1), acc_before: 0, acc_after: 1 };
+        assert_eq!(original.0[1], step);
+        assert_eq!(original.0[4].to_string(), "7 2 2 jmp -4");
+        assert_eq!(Trace::parse(&original.to_string()), Ok(original.clone()));
+        let (_, mut fixed) = cpu.repair().unwrap();
+        fixed.record();
+        fixed.run_until_loop_or_halt();
+        let fixed = fixed.take_trace().unwrap();
+        assert_eq!(original.first_divergence(&fixed), Some(4));
+        assert_eq!(original.first_divergence(&original), None);
+        let diff = original.side_by_side(&fixed);
+        assert_eq!(diff.lines().count(), 7);
+        assert!(diff.lines().nth(4).unwrap().starts_with("     4 *     7: jmp -4"));
+        assert_eq!(original.replay(cpu.clone()).unwrap().head(), 1);
+        let (_, fixed_cpu) = cpu.repair().unwrap();
+        assert_eq!(original.replay(fixed_cpu).err(), Some(4));
+        let err = Trace::parse("0 0 0 nop +0\n1 0 acc +1").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 5));
+    }
+    #[test]
+    fn debugger() {
+        let input = fs::read_to_string("input/example08").unwrap();
+        let mut dbg = Debugger::new(Cpu::new(&input).unwrap());
+        assert_eq!(dbg.current(), Some((0, &Op::Nop(0))));
+        assert_eq!(dbg.step(), Event::Step);
+        assert!(dbg.set_breakpoint(4));
+        assert_eq!(dbg.cont(), Event::Breakpoint(4));
+        assert_eq!(dbg.cpu().acc(), 5);
+        assert_eq!(dbg.current().unwrap().1.to_string(), "jmp -3");
+        assert!(dbg.clear_breakpoint(4));
+        dbg.watch_acc(true);
+        assert_eq!(dbg.cont(), Event::Loop(1));
+        assert_eq!(dbg.cont(), Event::Watch { old: 5, new: 6 });
+        dbg.watch_acc(false);
+        assert_eq!(dbg.cont(), Event::Loop(1));
+        assert_eq!((dbg.cpu().acc(), dbg.visits(1)), (10, 2));
+        let visited: Vec<_> = dbg.visited().collect();
+        assert_eq!(visited, [0, 1, 2, 3, 4, 6, 7]);
+        let mut dbg = Debugger::new(Cpu::new("acc +2\nnop +0").unwrap());
+        assert_eq!(dbg.cont(), Event::Halted);
+        assert_eq!((dbg.cpu().acc(), dbg.current()), (2, None));
+        let mut dbg = Debugger::new(Cpu::new("acc +2\njmp -2").unwrap());
+        assert_eq!(dbg.cont(), Event::OutOfBounds(-1));
+        assert_eq!(dbg.step(), Event::OutOfBounds(-1));
+        assert_eq!(dbg.cpu().out_of_bounds(), Some(-1));
+    }
+    #[test]
+    fn bad_instruction() {
+        let err = Cpu::new("nop +0\nacc +1\njump +4").err().unwrap();
+        assert_eq!((err.line, err.column), (3, 1));
+        assert_eq!(err.text, "jump");
+        let err = Cpu::new("nop +0\nacc 1x").err().unwrap();
+        assert_eq!((err.line, err.column), (2, 5));
     }
 }
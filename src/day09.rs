@@ -1,3 +1,6 @@
+//! Day 9: Encoding Error.
+use crate::parse::{number, ParseError};
+use crate::solution::Solution;
 use std::collections::VecDeque;
 
 fn add_num(deque: &mut VecDeque<usize>, num: usize) -> bool {
@@ -12,10 +15,11 @@ fn add_num(deque: &mut VecDeque<usize>, num: usize) -> bool {
     }
     false
 }
-fn xmas(preamble: &[&str], stream: &[&str]) -> usize {
-    let mut deque: VecDeque<usize> =
-        preamble.iter().map(|s| s.parse().unwrap()).collect();
-    for n in stream.iter().map(|s| s.parse().unwrap()) {
+/// Returns the first number in `stream` that isn't the sum of two of the
+/// preceding numbers.
+pub fn xmas(preamble: &[usize], stream: &[usize]) -> usize {
+    let mut deque: VecDeque<usize> = preamble.iter().cloned().collect();
+    for &n in stream {
         if !add_num(&mut deque, n) {
             return n;
         }
@@ -23,10 +27,12 @@ fn xmas(preamble: &[&str], stream: &[&str]) -> usize {
     panic!()
 }
 
-fn xmas2(input: &str, goal: usize) -> (usize, usize) {
+/// Finds a contiguous range summing to `goal` and returns its smallest and
+/// largest number.
+pub fn xmas2(nums: &[usize], goal: usize) -> (usize, usize) {
     let mut deque: VecDeque<usize> = VecDeque::new();
     let mut sum = 0;
-    for n in input.lines().map(|s| s.parse().unwrap()) {
+    for &n in nums {
         while sum > goal {
             sum -= deque.pop_front().unwrap();
         }
@@ -40,8 +46,8 @@ fn xmas2(input: &str, goal: usize) -> (usize, usize) {
     }
     panic!()
 }
-fn xmas2_v2(input: &str, goal: usize) -> (usize, usize) {
-    let nums: Vec<usize> = input.lines().map(|l| l.parse().unwrap()).collect();
+/// Same as [`xmas2`], using two indices instead of a deque.
+pub fn xmas2_v2(nums: &[usize], goal: usize) -> (usize, usize) {
     let mut sum = 0;
     let mut start = 0;
     let mut end = 0;
@@ -61,36 +67,73 @@ fn xmas2_v2(input: &str, goal: usize) -> (usize, usize) {
     }
     panic!()
 }
+
+/// Parses one number per line.
+pub fn parse(input: &str) -> Result<Vec<usize>, ParseError> {
+    input.lines().enumerate().map(|(i, l)| number(i, l, l)).collect()
+}
+
+const PREAMBLE: usize = 25;
+
+fn invalid_number(nums: &[usize]) -> usize {
+    xmas(&nums[..PREAMBLE], &nums[PREAMBLE..])
+}
+
+/// Solver for day 9.
+pub struct Day09;
+
+impl Solution for Day09 {
+    type Input = Vec<usize>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Vec<usize>, ParseError> {
+        let nums = parse(input)?;
+        if nums.len() <= PREAMBLE {
+            let expected = format!("more than {} numbers", PREAMBLE);
+            return Err(ParseError::end(input, expected));
+        }
+        Ok(nums)
+    }
+    fn part1(nums: &Vec<usize>) -> usize {
+        invalid_number(nums)
+    }
+    fn part2(nums: &Vec<usize>) -> usize {
+        let (min, max) = xmas2(nums, invalid_number(nums));
+        min + max
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs;
 
-    use super::{xmas, xmas2, xmas2_v2};
+    use super::{parse, xmas, xmas2, xmas2_v2};
     #[test]
     fn example() {
         let input = fs::read_to_string("input/example09").unwrap();
-        let lines: Vec<_> = input.lines().collect();
-        let ans = xmas(&lines[..5], &lines[5..]);
+        let nums = parse(&input).unwrap();
+        let ans = xmas(&nums[..5], &nums[5..]);
         assert_eq!(ans, 127);
     }
     #[test]
     fn part1() {
         let input = fs::read_to_string("input/day09").unwrap();
-        let lines: Vec<_> = input.lines().collect();
-        let ans = xmas(&lines[..25], &lines[25..]);
+        let nums = parse(&input).unwrap();
+        let ans = xmas(&nums[..25], &nums[25..]);
         assert_eq!(ans, 1504371145);
     }
     #[test]
     fn part2() {
         let input = fs::read_to_string("input/day09").unwrap();
-        let ans = xmas2(&input, 1504371145);
+        let ans = xmas2(&parse(&input).unwrap(), 1504371145);
         assert_eq!(ans.0 + ans.1, 183278487);
     }
 
     #[test]
     fn part2_v2() {
         let input = fs::read_to_string("input/day09").unwrap();
-        let ans = xmas2_v2(&input, 1504371145);
+        let ans = xmas2_v2(&parse(&input).unwrap(), 1504371145);
         assert_eq!(ans.0 + ans.1, 183278487);
     }
 }
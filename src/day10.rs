@@ -1,11 +1,19 @@
+//! Day 10: Adapter Array.
+use crate::parse::{number, ParseError};
+use crate::solution::Solution;
 use std::{
     collections::{HashMap, HashSet},
     iter,
 };
 
-fn do_part1(input: &str) -> (u32, u32) {
-    let mut adapters: Vec<u32> =
-        input.lines().map(|l| l.parse().unwrap()).collect();
+/// Parses the joltage of one adapter per line.
+pub fn parse(input: &str) -> Result<Vec<usize>, ParseError> {
+    input.lines().enumerate().map(|(i, l)| number(i, l, l)).collect()
+}
+
+/// Counts the 1-jolt and 3-jolt differences in the full adapter chain.
+pub fn do_part1(adapters: &[usize]) -> (usize, usize) {
+    let mut adapters = adapters.to_vec();
     adapters.push(0);
     adapters.sort_unstable();
     let mut plus_one = 0;
@@ -42,45 +50,63 @@ fn ways_to_reach_adapter(
     memo.insert(to_reach, ans);
     ans
 }
-fn do_part2(input: &str) -> usize {
+/// Counts the distinct adapter arrangements.
+pub fn do_part2(adapters: &[usize]) -> usize {
     // TODO: Dense set, use some bitvec or Vec<bool>
-    let available: HashSet<usize> = input
-        .lines()
-        .map(|l| l.parse().unwrap())
-        .chain(iter::once(0))
-        .collect();
+    let available: HashSet<usize> =
+        adapters.iter().cloned().chain(iter::once(0)).collect();
     let max = available.iter().max().unwrap();
     let mut memo = HashMap::new();
     ways_to_reach_adapter(*max, &available, &mut memo)
 }
 
+/// Solver for day 10.
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Vec<usize>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Vec<usize>, ParseError> {
+        parse(input)
+    }
+    fn part1(adapters: &Vec<usize>) -> usize {
+        let (ones, threes) = do_part1(adapters);
+        ones * threes
+    }
+    fn part2(adapters: &Vec<usize>) -> usize {
+        do_part2(adapters)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs;
 
-    use super::{do_part1, do_part2};
+    use super::{do_part1, do_part2, parse};
     #[test]
     fn example() {
         let input = fs::read_to_string("input/example10").unwrap();
-        let ans = do_part1(&input);
+        let ans = do_part1(&parse(&input).unwrap());
         assert_eq!(ans, (22, 10));
     }
     #[test]
     fn part1() {
         let input = fs::read_to_string("input/day10").unwrap();
-        let ans = do_part1(&input);
+        let ans = do_part1(&parse(&input).unwrap());
         assert_eq!(ans, (70, 27));
     }
     #[test]
     fn example2() {
         let input = fs::read_to_string("input/example10").unwrap();
-        let ans = do_part2(&input);
+        let ans = do_part2(&parse(&input).unwrap());
         dbg!(ans, 19208);
     }
     #[test]
     fn part2() {
         let input = fs::read_to_string("input/day10").unwrap();
-        let ans = do_part2(&input);
+        let ans = do_part2(&parse(&input).unwrap());
         assert_eq!(ans, 49607173328384);
     }
 }
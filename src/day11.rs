@@ -1,165 +1,269 @@
-#[derive(Clone, Copy)]
+//! Day 11: Seating System.
+use crate::automaton::{DenseAutomaton, Graph, Rule, Settled, Topology};
+use crate::grid::{Grid, NEIGHBORS8};
+use crate::parse::ParseError;
+use crate::solution::Solution;
+use std::fmt::{self, Display};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 enum State {
     Floor,
     Empty,
     Occupied,
 }
-struct Game(Vec<Vec<State>>);
 
-impl Game {
-    fn new(input: &str) -> Self {
-        let map = input
-            .lines()
-            .map(|l| {
-                l.chars()
-                    .map(|c| match c {
-                        '.' => State::Floor,
-                        'L' => State::Empty,
-                        _ => panic!(),
-                    })
-                    .collect()
-            })
-            .collect();
-        Game(map)
-    }
-    fn occupied_seats(&self) -> usize {
-        self.0
-            .iter()
-            .map(|row| {
-                row.iter()
-                    .map(|s| if let State::Occupied = s { 1 } else { 0 })
-                    .sum::<usize>()
-            })
-            .sum()
-    }
-    fn step(self) -> Self {
-        let mut next_map =
-            vec![vec![State::Floor; self.0[0].len()]; self.0.len()];
-        self.0
-            .iter()
-            .enumerate()
-            .flat_map(|(y, row)| {
-                row.iter().enumerate().map(move |(x, tile)| (x, y, tile))
-            })
-            .for_each(|(x, y, tile)| {
-                next_map[y][x] = self.next_state(*tile, x, y)
-            });
-
-        Game(next_map)
-    }
-    fn next_state(&self, tile: State, x: usize, y: usize) -> State {
-        match tile {
-            State::Floor => State::Floor,
-            State::Empty => {
-                if self.occupied_neighbors(x, y) == 0 {
-                    State::Occupied
-                } else {
-                    State::Empty
-                }
-            }
-            State::Occupied => {
-                if self.occupied_neighbors(x, y) >= 4 {
-                    State::Empty
-                } else {
-                    State::Occupied
-                }
-            }
+impl State {
+    /// Colors for PPM frames.
+    fn color(&self) -> [u8; 3] {
+        match self {
+            State::Floor => [40, 40, 40],
+            State::Empty => [90, 170, 90],
+            State::Occupied => [220, 60, 50],
         }
     }
-    fn occupied_neighbors(&self, x: usize, y: usize) -> usize {
-        let x = x as i64;
-        let y = y as i64;
-        [
-            (x - 1, y - 1),
-            (x, y - 1),
-            (x + 1, y - 1),
-            (x - 1, y),
-            (x + 1, y),
-            (x - 1, y + 1),
-            (x, y + 1),
-            (x + 1, y + 1),
-        ]
-        .iter()
-        .map(
-            |&pos| {
-                if let Some(State::Occupied) = self.get_tile(pos) {
-                    1
-                } else {
-                    0
-                }
-            },
-        )
-        .sum()
-    }
-    fn get_tile(&self, (x, y): (i64, i64)) -> Option<State> {
-        let max_x = (self.0[0].len() - 1) as i64;
-        let max_y = (self.0.len() - 1) as i64;
-        if 0 <= x && x <= max_x && 0 <= y && y <= max_y {
-            Some(self.0[y as usize][x as usize])
-        } else {
-            None
+}
+
+/// Prints the state in the puzzle's notation.
+impl Display for State {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let c = match self {
+            State::Floor => '.',
+            State::Empty => 'L',
+            State::Occupied => '#',
+        };
+        write!(f, "{}", c)
+    }
+}
+/// Which seats a seat takes into account.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Neighborhood {
+    /// The eight adjacent places, as in part 1.
+    Adjacent,
+    /// The first seat seen in each of the eight directions, as in part 2.
+    LineOfSight,
+}
+
+/// What lies past the edge of the layout.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Edges {
+    /// Nothing, as in the puzzle.
+    Bounded,
+    /// The opposite edge, so the layout wraps around like a torus.
+    Toroidal,
+}
+
+/// How seats are taken and left, and which seats are neighbors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SeatRules {
+    /// An empty seat is taken when exactly this many neighbors are
+    /// occupied.
+    pub birth: usize,
+    /// An occupied seat is left when at least this many neighbors are
+    /// occupied.
+    pub threshold: usize,
+    /// Which seats count as neighbors.
+    pub neighborhood: Neighborhood,
+    /// How many places a line-of-sight neighborhood looks past, or None to
+    /// look as far as there is room.
+    pub max_distance: Option<usize>,
+    /// What lies past the edges of the layout.
+    pub edges: Edges,
+}
+
+impl SeatRules {
+    /// The rules of part 1.
+    pub fn part1() -> Self {
+        SeatRules {
+            birth: 0,
+            threshold: 4,
+            neighborhood: Neighborhood::Adjacent,
+            max_distance: None,
+            edges: Edges::Bounded,
         }
     }
-    /////// PART 2 ////////////
-    fn occupied_in_view(&self, pos: &(i64, i64)) -> usize {
-        [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)]
-            .iter()
-            .map(|dir| {
-                if let Some(State::Occupied) = self.first_seat_in_dir(pos, dir)
-                {
-                    1
-                } else {
-                    0
-                }
-            })
-            .sum()
-    }
-    fn first_seat_in_dir(
-        &self,
-        pos: &(i64, i64),
-        dir: &(i64, i64),
-    ) -> Option<State> {
-        let add = |a: &(i64, i64), b: &(i64, i64)| (a.0 + b.0, a.1 + b.1);
-        let mut view = add(pos, dir);
-        while let Some(State::Floor) = self.get_tile(view) {
-            view = add(&view, dir);
+    /// The rules of part 2.
+    pub fn part2() -> Self {
+        SeatRules {
+            threshold: 5,
+            neighborhood: Neighborhood::LineOfSight,
+            ..Self::part1()
         }
-        self.get_tile(view)
     }
+    /// The rules as an automaton rule on seats.
+    pub fn rule(&self) -> Rule {
+        Rule::new([self.birth], 0..self.threshold)
+    }
+}
 
-    fn next_state_part2(&self, tile: State, x: usize, y: usize) -> State {
-        match tile {
-            State::Floor => State::Floor,
-            State::Empty => {
-                if self.occupied_in_view(&(x as i64, y as i64)) == 0 {
-                    State::Occupied
-                } else {
-                    State::Empty
-                }
+/// The seats of a layout, neighboring each other as the rules say.
+#[derive(Clone, Debug)]
+pub struct Seats {
+    layout: Grid<bool>,
+    seats: Vec<(usize, usize)>,
+    rules: SeatRules,
+}
+
+impl Seats {
+    /// The first seat seen from `pos` in direction `dir`, if any.
+    fn first_seat(
+        &self,
+        pos: (usize, usize),
+        dir: (i64, i64),
+    ) -> Option<(usize, usize)> {
+        let limit = match self.rules.neighborhood {
+            Neighborhood::Adjacent => 1,
+            Neighborhood::LineOfSight => {
+                self.rules.max_distance.unwrap_or(usize::MAX)
             }
-            State::Occupied => {
-                if self.occupied_in_view(&(x as i64, y as i64)) >= 5 {
-                    State::Empty
-                } else {
-                    State::Occupied
-                }
+        };
+        let mut p = pos;
+        for _ in 0..limit {
+            p = match self.rules.edges {
+                Edges::Bounded => self.layout.step(p, dir)?,
+                Edges::Toroidal => self.layout.step_wrapping(p, dir),
+            };
+            if p == pos {
+                // Wrapped all the way around.
+                return None;
+            }
+            if self.layout[p] {
+                return Some(p);
             }
         }
+        None
     }
-    fn step2(self) -> Self {
-        let mut next_map =
-            vec![vec![State::Floor; self.0[0].len()]; self.0.len()];
-        self.0
+}
+
+impl Topology for Seats {
+    type Cell = (usize, usize);
+    type Neighbors = Vec<(usize, usize)>;
+
+    fn neighbors(&self, pos: (usize, usize)) -> Vec<(usize, usize)> {
+        NEIGHBORS8
             .iter()
-            .enumerate()
-            .flat_map(|(y, row)| {
-                row.iter().enumerate().map(move |(x, tile)| (x, y, tile))
-            })
-            .for_each(|(x, y, tile)| {
-                next_map[y][x] = self.next_state_part2(*tile, x, y)
-            });
+            .filter_map(|&dir| self.first_seat(pos, dir))
+            .collect()
+    }
+    fn cells(&self) -> Option<&[(usize, usize)]> {
+        Some(&self.seats)
+    }
+}
+
+/// Rounds to simulate before giving up on the seating settling down.
+const MAX_ROUNDS: usize = 100_000;
+
+/// The seat layout.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Game(Grid<State>);
+
+impl Game {
+    /// Parses a layout of `.` floor, `L` empty seats and `#` occupied seats.
+    pub fn new(input: &str) -> Result<Self, ParseError> {
+        let map = Grid::parse(input, "`.`, `L` or `#`", |c| match c {
+            '.' => Some(State::Floor),
+            'L' => Some(State::Empty),
+            '#' => Some(State::Occupied),
+            _ => None,
+        })?;
+        Ok(Game(map))
+    }
+    /// Number of occupied seats.
+    pub fn occupied_seats(&self) -> usize {
+        self.0.iter().filter(|s| matches!(s, State::Occupied)).count()
+    }
+    /// The seating as an automaton following `rules`. Each seat's neighbors
+    /// are found once, so rounds cost the same however far seats look.
+    pub fn automaton(&self, rules: &SeatRules) -> DenseAutomaton<(usize, usize)> {
+        let layout = self.0.map(|s| !matches!(s, State::Floor));
+        let seats = layout.positions().filter(|(_, &s)| s).map(|(p, _)| p);
+        let seats = Seats { seats: seats.collect(), layout, rules: *rules };
+        let occupied = self
+            .0
+            .positions()
+            .filter(|(_, s)| matches!(s, State::Occupied))
+            .map(|(p, _)| p);
+        DenseAutomaton::new(Graph::new(&seats), rules.rule(), occupied)
+    }
+    /// Runs `rounds` rounds.
+    pub fn rounds(&self, rules: &SeatRules, rounds: usize) -> Self {
+        let mut seating = self.automaton(rules);
+        for _ in 0..rounds {
+            seating.step();
+        }
+        self.with_seating(&seating)
+    }
+    /// Runs until no seat changes, or until the layout repeats an earlier
+    /// one, giving up after `limit` rounds. Returns the layout reached and
+    /// whether it is stable or the start of a cycle.
+    pub fn settle(
+        &self,
+        rules: &SeatRules,
+        limit: usize,
+    ) -> Option<(Self, Settled)> {
+        let mut seating = self.automaton(rules);
+        let settled = seating.settle(limit)?;
+        Some((self.with_seating(&seating), settled))
+    }
+    /// This layout with the occupied seats of `seating`.
+    fn with_seating(&self, seating: &DenseAutomaton<(usize, usize)>) -> Self {
+        let (width, height) = (self.0.width(), self.0.height());
+        Game(Grid::from_fn(width, height, |pos| match self.0[pos] {
+            State::Floor => State::Floor,
+            _ if seating.is_live(pos) => State::Occupied,
+            _ => State::Empty,
+        }))
+    }
+    /// The layouts round by round under `rules`, starting with this one and
+    /// ending before the first layout seen before. The rounds are run once
+    /// to find where the layouts repeat, then again to yield them.
+    pub fn generations<'a>(
+        &'a self,
+        rules: &SeatRules,
+    ) -> impl Iterator<Item = Game> + 'a {
+        let mut seating = self.automaton(rules);
+        let rounds = match seating.clone().settle(usize::MAX) {
+            Some(Settled::Stable { steps }) => steps + 1,
+            Some(Settled::Cycle { start, period }) => start + period,
+            None => unreachable!("a finite automaton always repeats"),
+        };
+        (0..rounds).map(move |round| {
+            if round > 0 {
+                seating.step();
+            }
+            self.with_seating(&seating)
+        })
+    }
+    /// Renders the layout as a binary PPM image, `scale` pixels per place.
+    pub fn to_ppm(&self, scale: usize) -> Vec<u8> {
+        self.0.to_ppm(scale, State::color)
+    }
+}
 
-        Game(next_map)
+/// Prints the layout in the puzzle's notation, one line per row.
+impl Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Solver for day 11.
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = Game;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Game, ParseError> {
+        Game::new(input)
+    }
+    fn part1(game: &Game) -> usize {
+        let (game, _) = game.settle(&SeatRules::part1(), MAX_ROUNDS).unwrap();
+        game.occupied_seats()
+    }
+    fn part2(game: &Game) -> usize {
+        let (game, _) = game.settle(&SeatRules::part2(), MAX_ROUNDS).unwrap();
+        game.occupied_seats()
     }
 }
 
@@ -167,45 +271,82 @@ impl Game {
 mod tests {
     use std::fs;
 
-    use super::Game;
+    use super::{Edges, Game, SeatRules, Settled};
     #[test]
     fn example() {
         let input = fs::read_to_string("input/example11").unwrap();
-        let mut g = Game::new(&input);
-        for _ in 0..100 {
-            g = g.step();
-        }
-        let ans = g.occupied_seats();
-        assert_eq!(ans, 37);
+        let g = Game::new(&input).unwrap();
+        let (g, settled) = g.settle(&SeatRules::part1(), 100).unwrap();
+        assert_eq!(settled, Settled::Stable { steps: 5 });
+        assert_eq!(g.occupied_seats(), 37);
+        assert_eq!(g.rounds(&SeatRules::part1(), 1), g);
     }
     #[test]
     fn part1() {
         let input = fs::read_to_string("input/day11").unwrap();
-        let mut g = Game::new(&input);
-        for _ in 0..100 {
-            g = g.step();
-        }
+        let g = Game::new(&input).unwrap();
+        let (g, _) = g.settle(&SeatRules::part1(), 1000).unwrap();
         let ans = g.occupied_seats();
         assert_eq!(ans, 2222);
     }
     #[test]
     fn example2() {
         let input = fs::read_to_string("input/example11").unwrap();
-        let mut g = Game::new(&input);
-        for _ in 0..100 {
-            g = g.step2();
-        }
-        let ans = g.occupied_seats();
-        assert_eq!(ans, 26);
+        let g = Game::new(&input).unwrap();
+        let (g, settled) = g.settle(&SeatRules::part2(), 100).unwrap();
+        assert_eq!(settled, Settled::Stable { steps: 6 });
+        assert_eq!(g.occupied_seats(), 26);
+        assert_eq!(g.rounds(&SeatRules::part2(), 1), g);
+        let start = Game::new(&input).unwrap();
+        assert_eq!(start.rounds(&SeatRules::part2(), 5000), g);
     }
     #[test]
     fn part2() {
         let input = fs::read_to_string("input/day11").unwrap();
-        let mut g = Game::new(&input);
-        for _ in 0..100 {
-            g = g.step2();
-        }
+        let g = Game::new(&input).unwrap();
+        let (g, _) = g.settle(&SeatRules::part2(), 1000).unwrap();
         let ans = g.occupied_seats();
         assert_eq!(ans, 2032);
     }
+    #[test]
+    fn stable_layout() {
+        // A lone seat is taken in the first round and kept.
+        let g = Game::new("L.\n..").unwrap();
+        let (g, settled) = g.settle(&SeatRules::part1(), 10).unwrap();
+        assert_eq!(settled, Settled::Stable { steps: 1 });
+        assert_eq!(g.occupied_seats(), 1);
+        assert!(g.settle(&SeatRules::part1(), 0).is_none());
+    }
+    #[test]
+    fn display() {
+        let input = fs::read_to_string("input/example11").unwrap();
+        let g = Game::new(&input).unwrap();
+        assert_eq!(g.to_string(), input.trim_end());
+        let frames: Vec<_> = g.generations(&SeatRules::part1()).collect();
+        assert_eq!(frames.len(), 6);
+        assert!(frames[1].to_string().starts_with("#.##.##.##\n"));
+        assert_eq!(frames[5].occupied_seats(), 37);
+        let ppm = frames[5].to_ppm(3);
+        assert!(ppm.starts_with(b"P6\n30 30\n255\n"));
+    }
+    #[test]
+    fn variants() {
+        let input = fs::read_to_string("input/example11").unwrap();
+        let g = Game::new(&input).unwrap();
+        // Seeing one place ahead is the same as looking at adjacent seats.
+        let near = SeatRules { max_distance: Some(1), ..SeatRules::part2() };
+        let part1 = SeatRules { threshold: 5, ..SeatRules::part1() };
+        assert_eq!(g.rounds(&near, 3), g.rounds(&part1, 3));
+        // On a torus every seat of a full row sees both others, so with a
+        // threshold of 2 they are all taken and left in turn.
+        let g = Game::new("LLL\n...\n...").unwrap();
+        let crowded = SeatRules { threshold: 2, ..SeatRules::part2() };
+        let torus = SeatRules { edges: Edges::Toroidal, ..crowded };
+        let (g, settled) = g.settle(&torus, 10).unwrap();
+        assert_eq!(settled, Settled::Cycle { start: 0, period: 2 });
+        assert_eq!(g.occupied_seats(), 0);
+        let (g, settled) = g.settle(&crowded, 10).unwrap();
+        assert_eq!(settled, Settled::Stable { steps: 2 });
+        assert_eq!(g.occupied_seats(), 2);
+    }
 }
@@ -1,42 +1,65 @@
+//! Day 12: Rain Risk.
+use crate::parse::{number, ParseError};
+use crate::solution::Solution;
 use cgmath::Vector2;
-type V2d = Vector2<i64>;
+/// A position or direction; x points east and y north.
+pub type V2d = Vector2<i64>;
 
-enum Inst {
+/// A navigation instruction.
+pub enum Inst {
+    /// Move north.
     N(i64),
+    /// Move south.
     S(i64),
+    /// Move east.
     E(i64),
+    /// Move west.
     W(i64),
+    /// Turn left by the degrees given.
     L(i64),
+    /// Turn right by the degrees given.
     R(i64),
+    /// Move forward.
     F(i64),
 }
 
-fn parse_instructions(input: &str) -> Vec<Inst> {
+/// Parses one instruction per line, like `F10`.
+pub fn parse_instructions(input: &str) -> Result<Vec<Inst>, ParseError> {
     input
         .lines()
-        .map(|l| {
-            let (c, n) = l.split_at(1);
-            match c {
-                "N" => Inst::N(n.parse().unwrap()),
-                "S" => Inst::S(n.parse().unwrap()),
-                "E" => Inst::E(n.parse().unwrap()),
-                "W" => Inst::W(n.parse().unwrap()),
-                "L" => Inst::L(n.parse().unwrap()),
-                "R" => Inst::R(n.parse().unwrap()),
-                "F" => Inst::F(n.parse().unwrap()),
-                _ => panic!("unkown symbol"),
-            }
+        .enumerate()
+        .map(|(i, l)| {
+            let first = l.chars().next().map_or(0, char::len_utf8);
+            let (c, n) = l.split_at(first);
+            let n = || number(i, l, n);
+            Ok(match c {
+                "N" => Inst::N(n()?),
+                "S" => Inst::S(n()?),
+                "E" => Inst::E(n()?),
+                "W" => Inst::W(n()?),
+                "L" => Inst::L(n()?),
+                "R" => Inst::R(n()?),
+                "F" => Inst::F(n()?),
+                _ => {
+                    let expected = "one of `NSEWLRF`";
+                    return Err(ParseError::at(i, l, c, expected));
+                }
+            })
         })
         .collect()
 }
 
-struct Ship {
-    pos: V2d,
-    dir: V2d,
+/// A ship, and the direction it steers by.
+pub struct Ship {
+    /// Position, with x east and y north.
+    pub pos: V2d,
+    /// Heading in part 1, waypoint in part 2.
+    pub dir: V2d,
 }
 
 impl Ship {
-    fn new(pos: V2d, dir: V2d) -> Self {
+    /// A ship at `pos`, heading or with its waypoint at `dir`.
+    pub fn new(pos: V2d, dir: V2d) -> Self {
         Self { pos, dir }
     }
     fn turn_left(&mut self) {
@@ -45,7 +68,8 @@ impl Ship {
     fn turn_right(&mut self) {
         self.dir = V2d::new(self.dir.y, -self.dir.x);
     }
-    fn run(&mut self, instructions: &[Inst]) {
+    /// Moves the ship itself for N, S, E and W.
+    pub fn run(&mut self, instructions: &[Inst]) {
         for inst in instructions {
             match inst {
                 Inst::N(n) => self.pos += *n * V2d::new(0, 1),
@@ -70,7 +94,8 @@ impl Ship {
             }
         }
     }
-    fn run_part2(&mut self, instructions: &[Inst]) {
+    /// Moves the waypoint for N, S, E and W.
+    pub fn run_part2(&mut self, instructions: &[Inst]) {
         for inst in instructions {
             match inst {
                 Inst::N(n) => self.dir += *n * V2d::new(0, 1),
@@ -96,6 +121,30 @@ impl Ship {
         }
     }
 }
+
+/// Solver for day 12.
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input = Vec<Inst>;
+    type Output1 = i64;
+    type Output2 = i64;
+
+    fn parse(input: &str) -> Result<Vec<Inst>, ParseError> {
+        parse_instructions(input)
+    }
+    fn part1(instructions: &Vec<Inst>) -> i64 {
+        let mut ship = Ship::new(V2d::new(0, 0), V2d::new(1, 0));
+        ship.run(instructions);
+        ship.pos.x.abs() + ship.pos.y.abs()
+    }
+    fn part2(instructions: &Vec<Inst>) -> i64 {
+        let mut ship = Ship::new(V2d::new(0, 0), V2d::new(10, 1));
+        ship.run_part2(instructions);
+        ship.pos.x.abs() + ship.pos.y.abs()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs;
@@ -106,7 +155,7 @@ mod tests {
     #[test]
     fn example() {
         let input = "F10\nN3\nF7\nR90\nF11".to_string();
-        let instructions = parse_instructions(&input);
+        let instructions = parse_instructions(&input).unwrap();
         let mut ship = Ship::new(V2d::new(0, 0), V2d::new(1, 0));
         ship.run(&instructions);
         let ans = ship.pos.x.abs() + ship.pos.y.abs();
@@ -115,7 +164,7 @@ mod tests {
     #[test]
     fn part1() {
         let input = read_to_string("input/day12").unwrap();
-        let instructions = parse_instructions(&input);
+        let instructions = parse_instructions(&input).unwrap();
         let mut ship = Ship::new(V2d::new(0, 0), V2d::new(1, 0));
         ship.run(&instructions);
         let ans = ship.pos.x.abs() + ship.pos.y.abs();
@@ -124,7 +173,7 @@ mod tests {
     #[test]
     fn example2() {
         let input = "F10\nN3\nF7\nR90\nF11".to_string();
-        let instructions = parse_instructions(&input);
+        let instructions = parse_instructions(&input).unwrap();
         let mut ship = Ship::new(V2d::new(0, 0), V2d::new(10, 1));
         ship.run_part2(&instructions);
         let ans = ship.pos.x.abs() + ship.pos.y.abs();
@@ -133,10 +182,18 @@ mod tests {
     #[test]
     fn part2() {
         let input = read_to_string("input/day12").unwrap();
-        let instructions = parse_instructions(&input);
+        let instructions = parse_instructions(&input).unwrap();
         let mut ship = Ship::new(V2d::new(0, 0), V2d::new(10, 1));
         ship.run_part2(&instructions);
         let ans = ship.pos.x.abs() + ship.pos.y.abs();
         assert_eq!(ans, 52203);
     }
+    #[test]
+    fn bad_instruction() {
+        let err = parse_instructions("F10\nX3").err().unwrap();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert_eq!(err.text, "X");
+        let err = parse_instructions("F10\nN").err().unwrap();
+        assert_eq!((err.line, err.column), (2, 2));
+    }
 }
@@ -1,18 +1,39 @@
-fn parse(input: &str) -> (usize, Vec<usize>) {
+//! Day 13: Shuttle Search.
+use crate::parse::{number, ParseError};
+use crate::solution::Solution;
+
+/// Parses the earliest departure, and the buses in service as (offset, id).
+fn parse_notes(
+    input: &str,
+) -> Result<(usize, Vec<(usize, usize)>), ParseError> {
     let mut lines = input.lines();
-    let earliest = lines.next().unwrap().parse().unwrap();
-    let bus_ids = lines
-        .next()
-        .unwrap()
+    let missing = || ParseError::end(input, "another line");
+    let first = lines.next().ok_or_else(missing)?;
+    let earliest = number(0, first, first)?;
+    let second = lines.next().ok_or_else(missing)?;
+    let buses = second
         .split(',')
-        .filter(|&s| s != "x")
-        .map(|s| s.parse().unwrap())
-        .collect();
+        .enumerate()
+        .filter(|(_, s)| *s != "x")
+        .map(|(i, s)| match number(1, second, s)? {
+            0 => Err(ParseError::at(1, second, s, "a bus id above 0")),
+            id => Ok((i, id)),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if buses.is_empty() {
+        return Err(ParseError::at(1, second, second, "a bus in service"));
+    }
+    Ok((earliest, buses))
+}
 
-    (earliest, bus_ids)
+/// Parses the earliest departure and the ids of the buses in service.
+pub fn parse(input: &str) -> Result<(usize, Vec<usize>), ParseError> {
+    let (earliest, buses) = parse_notes(input)?;
+    Ok((earliest, buses.into_iter().map(|(_, id)| id).collect()))
 }
 
-fn find_wait_time_and_bus(
+/// Returns the shortest wait and the bus it is for.
+pub fn find_wait_time_and_bus(
     earliest: usize,
     bus_ids: &[usize],
 ) -> (usize, usize) {
@@ -26,27 +47,53 @@ fn find_wait_time_and_bus(
         .unwrap()
 }
 
-fn parse2(input: &str) -> Vec<(usize, usize)> {
-    input
-        .lines()
-        .nth(1)
-        .unwrap()
-        .split(',')
-        .enumerate()
-        .filter(|(_, s)| *s != "x")
-        .map(|(i, s)| (i, s.parse().unwrap()))
-        .collect()
+/// Parses the buses in service as (offset, id).
+pub fn parse2(input: &str) -> Result<Vec<(usize, usize)>, ParseError> {
+    Ok(parse_notes(input)?.1)
 }
 
-fn do_part2(start: usize, step: usize, buses: &[(usize, usize)]) -> usize {
+/// Finds the first time from `start`, in steps of `step`, at which each bus
+/// departs at its offset.
+pub fn do_part2(start: usize, step: usize, buses: &[(usize, usize)]) -> usize {
     for i in 0.. {
         let ans = start + i * step;
-        if buses.iter().all(|(wait, id)| (ans + wait) % id == 0) {
+        if buses.iter().all(|(wait, id)| (ans + wait).is_multiple_of(*id)) {
             return ans;
         }
     }
     panic!("didn't find ans");
 }
+
+/// Solver for day 13.
+pub struct Day13;
+
+impl Solution for Day13 {
+    /// Earliest departure, and the buses as (offset, id).
+    type Input = (usize, Vec<(usize, usize)>);
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_notes(input)
+    }
+    fn part1((earliest, buses): &Self::Input) -> usize {
+        let bus_ids: Vec<_> = buses.iter().map(|(_, id)| *id).collect();
+        let (wait, id) = find_wait_time_and_bus(*earliest, &bus_ids);
+        wait * id
+    }
+    fn part2((_, buses): &Self::Input) -> usize {
+        // Add one bus at a time, stepping by the product of the ids already
+        // lined up so that they stay lined up.
+        let mut ans = 0;
+        let mut step = 1;
+        for (i, (_, id)) in buses.iter().enumerate() {
+            ans = do_part2(ans, step, &buses[..=i]);
+            step *= id;
+        }
+        ans
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs;
@@ -54,14 +101,14 @@ mod tests {
     use super::{do_part2, find_wait_time_and_bus, parse, parse2};
     #[test]
     fn example() {
-        let (earliest, bus_ids) = parse("939\n7,13,x,x,59,x,31,19");
+        let (earliest, bus_ids) = parse("939\n7,13,x,x,59,x,31,19").unwrap();
         let ans = find_wait_time_and_bus(earliest, &bus_ids);
         assert_eq!(ans.0 * ans.1, 295);
     }
     #[test]
     fn part1() {
         let input = fs::read_to_string("input/day13").unwrap();
-        let (earliest, bus_ids) = parse(&input);
+        let (earliest, bus_ids) = parse(&input).unwrap();
         let ans = find_wait_time_and_bus(earliest, &bus_ids);
         assert_eq!(ans.0 * ans.1, 153);
     }
@@ -71,7 +118,7 @@ mod tests {
         //let input = "0\n1789,37,47,1889";
 
         let input = fs::read_to_string("input/day13").unwrap();
-        let buses = parse2(&input);
+        let buses = parse2(&input).unwrap();
         let ans = do_part2(0, 1, &buses[..2]);
         assert_eq!(ans, 325);
         let step = &buses[..2].iter().map(|(_, id)| id).product();
@@ -84,4 +131,11 @@ mod tests {
         let ans = do_part2(97272292, *step, &buses);
         assert_eq!(ans, 471793476184394);
     }
+    #[test]
+    fn bad_bus() {
+        let err = parse("939\n7,13,x,y").err().unwrap();
+        assert_eq!((err.line, err.column), (2, 8));
+        let err = parse("939\n").err().unwrap();
+        assert_eq!((err.line, err.column), (2, 1));
+    }
 }
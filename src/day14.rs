@@ -1,21 +1,67 @@
+//! Day 14: Docking Data.
+use crate::parse::{number, ParseError};
+use crate::solution::Solution;
 use bitvec::prelude::*;
 use scan_fmt::scan_fmt;
 use std::collections::HashMap;
 
-enum MaskBit {
+/// A bit of a mask.
+pub enum MaskBit {
+    /// `X`: left as is by values, floating in addresses.
     Floating,
+    /// `1`: set.
     True,
+    /// `0`: cleared, or left as is in addresses.
     False,
 }
 
-fn parse_mask(input: &str) -> Vec<MaskBit> {
+/// A line of the initialization program.
+pub enum Instruction {
+    /// Set the mask, most significant bit first.
+    Mask(Vec<MaskBit>),
+    /// Write a value to an address.
+    Mem(usize, usize),
+}
+
+/// Parses the 36 bit `mask`, found on the zero-based `line`.
+fn parse_mask(
+    line: usize,
+    line_text: &str,
+    mask: &str,
+) -> Result<Vec<MaskBit>, ParseError> {
+    if mask.chars().count() != 36 {
+        return Err(ParseError::at(line, line_text, mask, "a mask of 36 bits"));
+    }
+    mask.char_indices()
+        .map(|(j, c)| match c {
+            'X' => Ok(MaskBit::Floating),
+            '1' => Ok(MaskBit::True),
+            '0' => Ok(MaskBit::False),
+            _ => {
+                let text = &mask[j..j + c.len_utf8()];
+                Err(ParseError::at(line, line_text, text, "`X`, `1` or `0`"))
+            }
+        })
+        .collect()
+}
+
+/// Parses the initialization program, one instruction per line.
+pub fn parse(input: &str) -> Result<Vec<Instruction>, ParseError> {
     input
-        .chars()
-        .map(|c| match c {
-            'X' => MaskBit::Floating,
-            '1' => MaskBit::True,
-            '0' => MaskBit::False,
-            _ => panic!("unknown symbol"),
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let (a, b) = line.split_once(" = ").ok_or_else(|| {
+                ParseError::at(i, line, line, "`<target> = <value>`")
+            })?;
+            if a == "mask" {
+                Ok(Instruction::Mask(parse_mask(i, line, b)?))
+            } else {
+                let address = scan_fmt!(a, "mem[{}]", usize).map_err(|_| {
+                    ParseError::at(i, line, a, "`mask` or `mem[<address>]`")
+                })?;
+                Ok(Instruction::Mem(address, number(i, line, b)?))
+            }
         })
         .collect()
 }
@@ -37,34 +83,33 @@ fn mask_number(n: usize, mask: &[MaskBit]) -> usize {
     b.load()
 }
 
-fn do_part1(input: &str) -> usize {
+/// Runs the program with the mask applied to values.
+pub fn do_part1(program: &[Instruction]) -> usize {
     let mut memory: HashMap<usize, usize> = HashMap::new();
-    let mut mask = vec![];
-    for line in input.lines() {
-        let (a, b) = line.split_once(" = ").unwrap();
-        if a == "mask" {
-            mask = parse_mask(b);
-        } else {
-            let i = scan_fmt!(a, "mem[{}]", usize).unwrap();
-            let val = mask_number(b.parse().unwrap(), &mask);
-            memory.insert(i, val);
+    let mut mask: &[MaskBit] = &[];
+    for instruction in program {
+        match instruction {
+            Instruction::Mask(m) => mask = m,
+            Instruction::Mem(i, val) => {
+                memory.insert(*i, mask_number(*val, mask));
+            }
         }
     }
     memory.values().sum()
 }
 
-fn do_part2(input: &str) -> usize {
+/// Runs the program with the mask applied to addresses.
+pub fn do_part2(program: &[Instruction]) -> usize {
     let mut memory: HashMap<usize, usize> = HashMap::new();
-    let mut mask = vec![];
-    for line in input.lines() {
-        let (a, b) = line.split_once(" = ").unwrap();
-        if a == "mask" {
-            mask = parse_mask(b);
-        } else {
-            let i = scan_fmt!(a, "mem[{}]", usize).unwrap();
-            let adresses = mask_adress(i, &mask);
-            for i in adresses {
-                memory.insert(i, b.parse().unwrap());
+    let mut mask: &[MaskBit] = &[];
+    for instruction in program {
+        match instruction {
+            Instruction::Mask(m) => mask = m,
+            Instruction::Mem(i, val) => {
+                let adresses = mask_adress(*i, mask);
+                for i in adresses {
+                    memory.insert(i, *val);
+                }
             }
         }
     }
@@ -98,33 +143,63 @@ fn mask_adress(n: usize, mask: &[MaskBit]) -> Vec<usize> {
     }
     bitarrays.iter().map(|b| b.load()).collect()
 }
+
+/// Solver for day 14.
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = Vec<Instruction>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Vec<Instruction>, ParseError> {
+        parse(input)
+    }
+    fn part1(program: &Vec<Instruction>) -> usize {
+        do_part1(program)
+    }
+    fn part2(program: &Vec<Instruction>) -> usize {
+        do_part2(program)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs;
 
-    use super::{do_part1, do_part2};
+    use super::{do_part1, do_part2, parse};
     #[test]
     fn example() {
         let input = fs::read_to_string("input/example14").unwrap();
-        let ans = do_part1(&input);
+        let ans = do_part1(&parse(&input).unwrap());
         assert_eq!(ans, 165);
     }
     #[test]
     fn part1() {
         let input = fs::read_to_string("input/day14").unwrap();
-        let ans = do_part1(&input);
+        let ans = do_part1(&parse(&input).unwrap());
         assert_eq!(ans, 9967721333886);
     }
     #[test]
     fn example2() {
         let input = fs::read_to_string("input/example14_part2").unwrap();
-        let ans = do_part2(&input);
+        let ans = do_part2(&parse(&input).unwrap());
         assert_eq!(ans, 208);
     }
     #[test]
     fn part2() {
         let input = fs::read_to_string("input/day14").unwrap();
-        let ans = do_part2(&input);
+        let ans = do_part2(&parse(&input).unwrap());
         assert_eq!(ans, 4355897790573);
     }
+    #[test]
+    fn bad_mask() {
+        let input = "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X\nmem[8] = 11";
+        assert!(parse(input).is_ok());
+        let input = "mem[8] = 11\nmask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX2X";
+        let err = parse(input).err().unwrap();
+        assert_eq!((err.line, err.column), (2, 42));
+        let err = parse("mem(8) = 11").err().unwrap();
+        assert_eq!((err.line, err.column, err.text.as_str()), (1, 1, "mem(8)"));
+    }
 }
@@ -1,6 +1,10 @@
+//! Day 15: Rambunctious Recitation.
+use crate::parse::{number, ParseError};
+use crate::solution::Solution;
 use std::collections::HashMap;
 
-fn do_part1(start_numbers: &[usize], end_turn: usize) -> usize {
+/// Plays the memory game and returns the number spoken at `end_turn`.
+pub fn do_part1(start_numbers: &[usize], end_turn: usize) -> usize {
     let mut memory: HashMap<usize, usize> =
         start_numbers.iter().enumerate().map(|(i, &n)| (n, i + 1)).collect();
     let mut last = 0;
@@ -13,6 +17,27 @@ fn do_part1(start_numbers: &[usize], end_turn: usize) -> usize {
     }
     last
 }
+
+/// Solver for day 15.
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Input = Vec<usize>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Vec<usize>, ParseError> {
+        let line = input.lines().next().unwrap_or("");
+        line.trim().split(',').map(|s| number(0, line, s)).collect()
+    }
+    fn part1(start_numbers: &Vec<usize>) -> usize {
+        do_part1(start_numbers, 2020)
+    }
+    fn part2(start_numbers: &Vec<usize>) -> usize {
+        do_part1(start_numbers, 30000000)
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
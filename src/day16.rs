@@ -1,44 +1,74 @@
+//! Day 16: Ticket Translation.
+use crate::parse::{number, sections, ParseError};
+use crate::solution::Solution;
 use std::ops::RangeInclusive;
-type Ranges = Vec<RangeInclusive<usize>>;
-struct Rule(String, Ranges);
-type Ticket = Vec<usize>;
+/// The valid values of a field.
+pub type Ranges = Vec<RangeInclusive<usize>>;
+/// A ticket field with its valid ranges.
+pub struct Rule(pub String, pub Ranges);
+/// A ticket's values, in field order.
+pub type Ticket = Vec<usize>;
 
-fn parse(input: &str) -> (Vec<Rule>, Ticket, Vec<Ticket>) {
-    let mut sections = input.split("\r\n\r\n");
-    let rules_sec = sections.next().unwrap();
-    let rules = rules_sec
-        .lines()
-        .map(|l| {
-            let (field, ranges) = l.split_once(": ").unwrap();
-            let ranges = ranges
-                .split(" or ")
-                .map(|r| {
-                    let (s, e) = r
-                        .split_once('-')
-                        .map(|(s, e)| (s.parse().unwrap(), e.parse().unwrap()))
-                        .unwrap();
-                    s..=e
-                })
-                .collect();
-            Rule(field.to_string(), ranges)
+fn parse_rule(i: usize, l: &str) -> Result<Rule, ParseError> {
+    let (field, ranges) = l
+        .split_once(": ")
+        .ok_or_else(|| ParseError::at(i, l, l, "`<field>: <ranges>`"))?;
+    let ranges = ranges
+        .split(" or ")
+        .map(|r| {
+            let (s, e) = r
+                .split_once('-')
+                .ok_or_else(|| ParseError::at(i, l, r, "`<start>-<end>`"))?;
+            Ok(number(i, l, s)?..=number(i, l, e)?)
         })
-        .collect();
-    let my_sec = sections.next().unwrap();
-    let my_ticket: Ticket = my_sec
+        .collect::<Result<_, _>>()?;
+    Ok(Rule(field.to_string(), ranges))
+}
+
+fn parse_ticket(i: usize, l: &str, fields: usize) -> Result<Ticket, ParseError> {
+    let ticket: Ticket =
+        l.split(',').map(|s| number(i, l, s)).collect::<Result<_, _>>()?;
+    if ticket.len() != fields {
+        return Err(ParseError::at(i, l, l, format!("{} values", fields)));
+    }
+    Ok(ticket)
+}
+
+/// Parses the rules, your ticket and the nearby tickets.
+pub fn parse(
+    input: &str,
+) -> Result<(Vec<Rule>, Ticket, Vec<Ticket>), ParseError> {
+    let mut parts = sections(input).into_iter();
+    let mut next_section = || {
+        parts
+            .next()
+            .ok_or_else(|| ParseError::end(input, "another section"))
+    };
+    let (first, rules_sec) = next_section()?;
+    let rules = rules_sec
         .lines()
-        .nth(1)
-        .map(|l| l.split(',').map(|s| s.parse().unwrap()).collect())
-        .unwrap();
-    let nearby_sec = sections.next().unwrap();
-    let nearby_tickets: Vec<Ticket> = nearby_sec
+        .enumerate()
+        .map(|(i, l)| parse_rule(first + i, l))
+        .collect::<Result<Vec<_>, _>>()?;
+    let (first, my_sec) = next_section()?;
+    let my_ticket = match my_sec.lines().nth(1) {
+        Some(l) => parse_ticket(first + 1, l, rules.len())?,
+        None => {
+            return Err(ParseError::end(my_sec, "your ticket").offset(first))
+        }
+    };
+    let (first, nearby_sec) = next_section()?;
+    let nearby_tickets = nearby_sec
         .lines()
+        .enumerate()
         .skip(1)
-        .map(|l| l.split(',').map(|s| s.parse().unwrap()).collect())
-        .collect();
-    (rules, my_ticket, nearby_tickets)
+        .map(|(i, l)| parse_ticket(first + i, l, rules.len()))
+        .collect::<Result<_, _>>()?;
+    Ok((rules, my_ticket, nearby_tickets))
 }
 
-fn do_part1(rules: &[Rule], nearby_tickets: &[Ticket]) -> usize {
+/// Sums the values that are not valid for any field.
+pub fn do_part1(rules: &[Rule], nearby_tickets: &[Ticket]) -> usize {
     nearby_tickets
         .iter()
         .map(|ticket| {
@@ -59,7 +89,8 @@ fn do_part1(rules: &[Rule], nearby_tickets: &[Ticket]) -> usize {
         .sum()
 }
 
-fn do_part2(
+/// Multiplies the departure fields of `my_ticket`.
+pub fn do_part2(
     rules: &[Rule],
     my_ticket: &[usize],
     nearby_tickets: &[Ticket],
@@ -115,6 +146,25 @@ fn do_part2(
         .product()
 }
 
+/// Solver for day 16.
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Input = (Vec<Rule>, Ticket, Vec<Ticket>);
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+    fn part1((rules, _, nearby): &Self::Input) -> usize {
+        do_part1(rules, nearby)
+    }
+    fn part2((rules, my_ticket, nearby): &Self::Input) -> usize {
+        do_part2(rules, my_ticket, nearby)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs;
@@ -123,21 +173,21 @@ mod tests {
     #[test]
     fn example() {
         let input = fs::read_to_string("input/example16").unwrap();
-        let (rules, _, nearby) = parse(&input);
+        let (rules, _, nearby) = parse(&input).unwrap();
         let ans = do_part1(&rules, &nearby);
         assert_eq!(ans, 71);
     }
     #[test]
     fn part1() {
         let input = fs::read_to_string("input/day16").unwrap();
-        let (rules, _, nearby) = parse(&input);
+        let (rules, _, nearby) = parse(&input).unwrap();
         let ans = do_part1(&rules, &nearby);
         assert_eq!(ans, 21978);
     }
     #[test]
     fn part2() {
         let input = fs::read_to_string("input/day16").unwrap();
-        let (rules, my_ticket, nearby) = parse(&input);
+        let (rules, my_ticket, nearby) = parse(&input).unwrap();
         let ans = do_part2(&rules, &my_ticket, &nearby);
         assert_eq!(ans, 1053686852011);
     }
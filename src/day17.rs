@@ -1,132 +1,227 @@
-use cgmath::Vector3;
-use cgmath::Vector4;
-use itertools::Itertools;
-use std::collections::HashSet;
+//! Day 17: Conway Cubes.
+use crate::automaton::{Automaton, Lattice, Mirrored, Rule};
+use crate::grid::Grid;
+use crate::parse::ParseError;
+use crate::solution::Solution;
+use std::fmt::{self, Display};
 
-type V3d = Vector3<i64>;
-type V4d = Vector4<i64>;
-#[derive(Debug)]
-struct ConwayCube(HashSet<V3d>);
+/// Checks that the starting slice is made of `.` and `#`.
+fn check_slice(input: &str) -> Result<(), ParseError> {
+    for (i, line) in input.lines().enumerate() {
+        if let Some((j, c)) =
+            line.chars().enumerate().find(|(_, c)| !".#".contains(*c))
+        {
+            return Err(ParseError::new(i, j, &c.to_string(), "`.` or `#`"));
+        }
+    }
+    Ok(())
+}
 
-impl ConwayCube {
-    fn parse(input: &str) -> Self {
-        let map = input
-            .lines()
-            .enumerate()
-            .flat_map(|(y, line)| {
-                line.chars().enumerate().filter_map(move |(x, c)| {
-                    if c == '#' {
-                        Some(V3d::new(x as i64, y as i64, 0))
-                    } else {
-                        None
-                    }
-                })
+/// The active cells of the starting slice, with every coordinate past `x`
+/// and `y` at 0.
+fn parse_slice<const N: usize>(input: &str) -> Result<Vec<[i64; N]>, ParseError> {
+    check_slice(input)?;
+    let cells = input
+        .lines()
+        .enumerate()
+        .flat_map(|(y, line)| {
+            line.chars().enumerate().filter_map(move |(x, c)| {
+                if c == '#' {
+                    let mut cell = [0; N];
+                    cell[0] = x as i64;
+                    cell[1] = y as i64;
+                    Some(cell)
+                } else {
+                    None
+                }
             })
-            .collect();
-        Self(map)
-    }
-    fn run(&mut self) {
-        let mut next_map = HashSet::with_capacity(self.0.len());
-        let mut to_visit = HashSet::with_capacity(self.0.len());
-        for pos in self.0.iter() {
-            let neighbors = get_neighbors_3d(pos);
-            to_visit.extend(neighbors.iter().cloned());
+        })
+        .collect();
+    Ok(cells)
+}
+
+/// The active cubes of an `N`-dimensional pocket dimension.
+#[derive(Clone, Debug)]
+pub struct Pocket<const N: usize>(pub Automaton<Lattice<N>>);
+
+/// The 3-D pocket dimension of part 1.
+pub type ConwayCube = Pocket<3>;
+/// The 4-D pocket dimension of part 2.
+pub type ConwayHyperCube = Pocket<4>;
+
+impl<const N: usize> Pocket<N> {
+    /// Parses the starting 2-D slice. Panics if `N` is below 2.
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        Self::with_rule(input, "B3/S23")
+    }
+    /// Parses the starting 2-D slice, to run with `rule` in B/S notation
+    /// rather than B3/S23. Births on 0 neighbors are rejected.
+    pub fn with_rule(input: &str, rule: &str) -> Result<Self, ParseError> {
+        assert!(N >= 2, "a pocket dimension needs at least 2 dimensions");
+        let rule = Rule::parse_infinite(rule)?;
+        let cells = parse_slice(input)?;
+        Ok(Self(Automaton::new(Lattice::new(), rule, cells)))
+    }
+    /// Runs one cycle.
+    pub fn run(&mut self) {
+        self.0.step()
+    }
+    /// Number of active cubes after the six-cycle boot process.
+    pub fn boot(&self) -> usize {
+        let mut pocket = self.clone();
+        for _ in 0..6 {
+            pocket.run()
         }
-        for visit in to_visit.iter() {
-            let neighbors = get_neighbors_3d(visit);
-            let alive_neighbors =
-                neighbors.iter().filter(|n| self.0.contains(n)).count();
-            if (self.0.contains(visit) && (2..=3).contains(&alive_neighbors))
-                || (!self.0.contains(visit) && alive_neighbors == 3)
-            {
-                next_map.insert(*visit);
-            }
+        pocket.0.len()
+    }
+
+    /// The starting state and the `cycles` after it, each headed like in
+    /// the puzzle's walkthrough (`After 2 cycles:`).
+    pub fn walkthrough(&self, cycles: usize) -> String {
+        let mut pocket = self.clone();
+        let mut text = format!("Before any cycles:\n\n{}\n", pocket);
+        for cycle in 1..=cycles {
+            pocket.run();
+            let s = if cycle == 1 { "" } else { "s" };
+            text += &format!("\n\nAfter {} cycle{}:\n\n{}\n", cycle, s, pocket);
         }
-        self.0 = next_map;
+        text
     }
 }
 
-fn get_neighbors_3d(p: &V3d) -> [V3d; 26] {
-    let mut res = [V3d::new(0, 0, 0); 26];
-    let x_vals = [p.x, p.x - 1, p.x + 1];
-    let y_vals = [p.y, p.y - 1, p.y + 1];
-    let z_vals = [p.z, p.z - 1, p.z + 1];
-    x_vals
-        .iter()
-        .cartesian_product(y_vals.iter().cartesian_product(z_vals.iter()))
-        .skip(1)
-        .enumerate()
-        .for_each(|(i, (x, (y, z)))| res[i] = V3d::new(*x, *y, *z));
-    res
+/// The label of an axis past `x` and `y`.
+fn axis_name(axis: usize) -> String {
+    match axis {
+        2 => "z".to_string(),
+        3 => "w".to_string(),
+        _ => format!("x{}", axis),
+    }
 }
-#[derive(Debug)]
-struct ConwayHyperCube(HashSet<V4d>);
 
-impl ConwayHyperCube {
-    fn parse(input: &str) -> Self {
-        let map = input
-            .lines()
-            .enumerate()
-            .flat_map(|(y, line)| {
-                line.chars().enumerate().filter_map(move |(x, c)| {
-                    if c == '#' {
-                        Some(V4d::new(x as i64, y as i64, 0, 0))
-                    } else {
-                        None
-                    }
+/// Prints every 2-D slice of the bounding box of the active cubes in the
+/// puzzle's notation, each labelled like `z=-1, w=0`, with `z` varying
+/// fastest. Prints nothing if no cube is active.
+impl<const N: usize> Display for Pocket<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let live = self.0.live();
+        if live.is_empty() {
+            return Ok(());
+        }
+        let (mut min, mut max) = ([i64::MAX; N], [i64::MIN; N]);
+        for cell in live {
+            for axis in 0..N {
+                min[axis] = min[axis].min(cell[axis]);
+                max[axis] = max[axis].max(cell[axis]);
+            }
+        }
+        let mut slices = vec![min];
+        for axis in (2..N).rev() {
+            slices = slices
+                .into_iter()
+                .flat_map(|s| {
+                    (min[axis]..=max[axis]).map(move |c| {
+                        let mut s = s;
+                        s[axis] = c;
+                        s
+                    })
                 })
-            })
-            .collect();
-        Self(map)
-    }
-    fn run(&mut self) {
-        let mut next_map = HashSet::with_capacity(self.0.len());
-        let mut to_visit = HashSet::with_capacity(self.0.len());
-        for pos in self.0.iter() {
-            let neighbors = get_neighbors_4d(pos);
-            to_visit.extend(neighbors.iter().cloned());
+                .collect();
         }
-        for visit in to_visit.iter() {
-            let neighbors = get_neighbors_4d(visit);
-            let alive_neighbors =
-                neighbors.iter().filter(|n| self.0.contains(n)).count();
-            if (self.0.contains(visit) && (2..=3).contains(&alive_neighbors))
-                || (!self.0.contains(visit) && alive_neighbors == 3)
-            {
-                next_map.insert(*visit);
+        let width = (max[0] - min[0] + 1) as usize;
+        let height = (max[1] - min[1] + 1) as usize;
+        for (i, slice) in slices.iter().enumerate() {
+            if i > 0 {
+                write!(f, "\n\n")?;
+            }
+            if N > 2 {
+                let label: Vec<_> = (2..N)
+                    .map(|axis| format!("{}={}", axis_name(axis), slice[axis]))
+                    .collect();
+                writeln!(f, "{}", label.join(", "))?;
             }
+            let grid = Grid::from_fn(width, height, |(x, y)| {
+                let mut cell = *slice;
+                cell[0] = min[0] + x as i64;
+                cell[1] = min[1] + y as i64;
+                if live.contains(&cell) {
+                    '#'
+                } else {
+                    '.'
+                }
+            });
+            write!(f, "{}", grid)?;
         }
-        self.0 = next_map;
+        Ok(())
     }
 }
 
-fn get_neighbors_4d(p: &V4d) -> [V4d; 80] {
-    let mut res = [V4d::new(0, 0, 0, 0); 80];
-    let x_vals = [p.x, p.x - 1, p.x + 1];
-    let y_vals = [p.y, p.y - 1, p.y + 1];
-    let z_vals = [p.z, p.z - 1, p.z + 1];
-    let w_vals = [p.w, p.w - 1, p.w + 1];
-    x_vals
-        .iter()
-        .cartesian_product(
-            y_vals.iter().cartesian_product(
-                z_vals.iter().cartesian_product(w_vals.iter()),
-            ),
-        )
-        .skip(1)
-        .enumerate()
-        .for_each(|(i, (x, (y, (z, w))))| res[i] = V4d::new(*x, *y, *z, *w));
-    res
+/// An `N`-dimensional pocket dimension storing only the cubes with no
+/// negative coordinate past `x` and `y`. A pocket booted from a flat slice
+/// stays mirror-symmetric in those axes, so this gives the same counts as
+/// [`Pocket`] while simulating about `2^(N-2)` times fewer cubes.
+#[derive(Clone, Debug)]
+pub struct SymmetricPocket<const N: usize>(pub Automaton<Mirrored<N>>);
+
+impl<const N: usize> SymmetricPocket<N> {
+    /// Parses the starting 2-D slice. Panics if `N` is below 2.
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        Self::with_rule(input, "B3/S23")
+    }
+    /// Parses the starting 2-D slice, to run with `rule` in B/S notation
+    /// rather than B3/S23. Births on 0 neighbors are rejected.
+    pub fn with_rule(input: &str, rule: &str) -> Result<Self, ParseError> {
+        assert!(N >= 2, "a pocket dimension needs at least 2 dimensions");
+        let rule = Rule::parse_infinite(rule)?;
+        let cells = parse_slice(input)?;
+        Ok(Self(Automaton::new(Mirrored::new(2), rule, cells)))
+    }
+    /// Runs one cycle.
+    pub fn run(&mut self) {
+        self.0.step()
+    }
+    /// Number of active cubes in the whole pocket dimension.
+    pub fn active(&self) -> usize {
+        let topology = self.0.topology();
+        self.0.live().iter().map(|&c| topology.multiplicity(c)).sum()
+    }
+    /// Number of active cubes after the six-cycle boot process.
+    pub fn boot(&self) -> usize {
+        let mut pocket = self.clone();
+        for _ in 0..6 {
+            pocket.run()
+        }
+        pocket.active()
+    }
+}
+
+/// Solver for day 17.
+pub struct Day17;
+
+impl Solution for Day17 {
+    type Input = (ConwayCube, ConwayHyperCube);
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Ok((ConwayCube::parse(input)?, ConwayHyperCube::parse(input)?))
+    }
+    fn part1((cube, _): &Self::Input) -> usize {
+        cube.boot()
+    }
+    fn part2((_, hyper_cube): &Self::Input) -> usize {
+        hyper_cube.boot()
+    }
 }
+
 #[cfg(test)]
 mod tests {
     use std::fs;
 
-    use super::{ConwayCube, ConwayHyperCube};
+    use super::{ConwayCube, ConwayHyperCube, Pocket, SymmetricPocket};
     #[test]
     fn example() {
         let input = fs::read_to_string("input/example17").unwrap();
-        let mut map = ConwayCube::parse(&input);
+        let mut map = ConwayCube::parse(&input).unwrap();
         for _ in 0..6 {
             map.run()
         }
@@ -135,7 +230,7 @@ mod tests {
     #[test]
     fn part1() {
         let input = fs::read_to_string("input/day17").unwrap();
-        let mut map = ConwayCube::parse(&input);
+        let mut map = ConwayCube::parse(&input).unwrap();
         for _ in 0..6 {
             map.run()
         }
@@ -144,7 +239,7 @@ mod tests {
     #[test]
     fn example_part2() {
         let input = fs::read_to_string("input/example17").unwrap();
-        let mut map = ConwayHyperCube::parse(&input);
+        let mut map = ConwayHyperCube::parse(&input).unwrap();
         for _ in 0..6 {
             map.run()
         }
@@ -153,10 +248,73 @@ mod tests {
     #[test]
     fn part2() {
         let input = fs::read_to_string("input/day17").unwrap();
-        let mut map = ConwayHyperCube::parse(&input);
+        let mut map = ConwayHyperCube::parse(&input).unwrap();
         for _ in 0..6 {
             map.run()
         }
         assert_eq!(map.0.len(), 1392);
     }
+    #[test]
+    fn higher_dimensions() {
+        let input = fs::read_to_string("input/example17").unwrap();
+        assert_eq!(Pocket::<3>::parse(&input).unwrap().boot(), 112);
+        assert_eq!(Pocket::<5>::parse(&input).unwrap().boot(), 5760);
+    }
+    #[test]
+    fn symmetric() {
+        let input = fs::read_to_string("input/day17").unwrap();
+        assert_eq!(SymmetricPocket::<3>::parse(&input).unwrap().boot(), 247);
+        assert_eq!(SymmetricPocket::<4>::parse(&input).unwrap().boot(), 1392);
+        let input = fs::read_to_string("input/example17").unwrap();
+        let mut pocket = SymmetricPocket::<4>::parse(&input).unwrap();
+        pocket.run();
+        assert_eq!(pocket.active(), 29);
+        assert_eq!(SymmetricPocket::<6>::parse(&input).unwrap().boot(), 35936);
+    }
+    #[test]
+    fn display() {
+        let input = fs::read_to_string("input/example17").unwrap();
+        let cube = ConwayCube::parse(&input).unwrap();
+        let text = cube.walkthrough(1);
+        assert!(text.starts_with("Before any cycles:\n\nz=0\n.#.\n..#\n###\n\n\n"));
+        assert!(text.ends_with(
+            "After 1 cycle:\n\n\
+             z=-1\n#..\n..#\n.#.\n\n\
+             z=0\n#.#\n.##\n.#.\n\n\
+             z=1\n#..\n..#\n.#.\n"
+        ));
+        let mut hyper_cube = ConwayHyperCube::parse(&input).unwrap();
+        hyper_cube.run();
+        let text = hyper_cube.to_string();
+        assert!(text.starts_with("z=-1, w=-1\n#..\n..#\n.#.\n\nz=0, w=-1\n"));
+        assert_eq!(text.matches(", w=").count(), 9);
+        assert_eq!(Pocket::<2>::parse("#.\n.#").unwrap().to_string(), "#.\n.#");
+    }
+    #[test]
+    fn rules() {
+        let input = fs::read_to_string("input/example17").unwrap();
+        assert_eq!(ConwayCube::with_rule(&input, "B3/S23").unwrap().boot(), 112);
+        // With births on 6 too the example grows faster, in both modes.
+        let rule = "B36/S23";
+        let cube = ConwayCube::with_rule(&input, rule).unwrap().boot();
+        assert!(cube > 112);
+        let half = SymmetricPocket::<3>::with_rule(&input, rule);
+        assert_eq!(half.unwrap().boot(), cube);
+        let pocket = ConwayHyperCube::with_rule(&input, rule).unwrap();
+        let half = SymmetricPocket::<4>::with_rule(&input, rule).unwrap();
+        assert_eq!(half.boot(), pocket.boot());
+        // Space is infinite, so nothing can be born with no live neighbors.
+        let err = ConwayCube::with_rule(&input, "B03/S23").err().unwrap();
+        assert_eq!((err.line, err.column), (1, 2));
+        let err = SymmetricPocket::<3>::with_rule(&input, "B5,0-2/S3").err().unwrap();
+        assert_eq!((err.line, err.column), (1, 4));
+        assert!(ConwayCube::with_rule(&input, "B10/S0").is_err());
+        assert!(ConwayCube::with_rule(&input, "B10,12/S0").is_ok());
+        assert!(ConwayCube::with_rule(&input, "B3/S").is_ok());
+    }
+    #[test]
+    fn bad_slice() {
+        let err = ConwayCube::parse(".#.\n..#\n#?#").err().unwrap();
+        assert_eq!((err.line, err.column), (3, 2));
+    }
 }
@@ -1,9 +1,15 @@
+//! Day 18: Operation Order.
+use crate::parse::ParseError;
+use crate::solution::Solution;
+
 enum Ops {
     Add,
     Mul,
 }
 // TODO: Reuse part2 for part1, make binding powers a variable?
-fn eval_part1(mut pos: usize, chars: &[char]) -> (usize, usize) {
+/// Evaluates from `pos` with + and * binding equally, until the end or a
+/// closing parenthesis. Returns the position after it and the value.
+pub fn eval_part1(mut pos: usize, chars: &[char]) -> (usize, usize) {
     let mut res = 0;
     let mut op = Ops::Add;
     while pos < chars.len() {
@@ -82,17 +88,97 @@ fn eval_part2(pos: &mut usize, chars: &[char], bind_pow: u8) -> usize {
     lhs
 }
 
-fn do_part2(input: &str) -> usize {
+/// Evaluates an expression with + binding tighter than *.
+pub fn do_part2(input: &str) -> usize {
     let characters: Vec<_> =
         input.chars().filter(|c| !c.is_whitespace()).collect();
-    eval_part2(&mut 0, &characters, 0)
+    eval_precedence(&characters)
+}
+
+/// Evaluates a parsed expression with + binding tighter than *.
+pub fn eval_precedence(expression: &[char]) -> usize {
+    eval_part2(&mut 0, expression, 0)
+}
+
+/// An expression's digits, operators and parentheses, without spaces.
+pub type Expression = Vec<char>;
+
+/// Parses one expression per line.
+pub fn parse_expressions(input: &str) -> Result<Vec<Expression>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            check_expression(i, line)?;
+            Ok(line.chars().filter(|c| !c.is_whitespace()).collect())
+        })
+        .collect()
+}
+
+/// Checks that `line` is a well-formed expression of single digits, `+`, `*`
+/// and parentheses.
+fn check_expression(i: usize, line: &str) -> Result<(), ParseError> {
+    let mut expect_operand = true;
+    let mut depth = 0;
+    for (j, c) in line.chars().enumerate() {
+        let expected = match (expect_operand, c) {
+            (_, ' ') => continue,
+            (true, '1'..='9') => {
+                expect_operand = false;
+                continue;
+            }
+            (true, '(') => {
+                depth += 1;
+                continue;
+            }
+            (false, '+') | (false, '*') => {
+                expect_operand = true;
+                continue;
+            }
+            (false, ')') if depth > 0 => {
+                depth -= 1;
+                continue;
+            }
+            (true, _) => "a digit or `(`",
+            (false, _) if depth > 0 => "`+`, `*` or `)`",
+            (false, _) => "`+` or `*`",
+        };
+        return Err(ParseError::new(i, j, &c.to_string(), expected));
+    }
+    if expect_operand {
+        Err(ParseError::end(line, "a digit or `(`").offset(i))
+    } else if depth > 0 {
+        Err(ParseError::end(line, "`)`").offset(i))
+    } else {
+        Ok(())
+    }
+}
+
+/// Solver for day 18.
+pub struct Day18;
+
+impl Solution for Day18 {
+    type Input = Vec<Expression>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_expressions(input)
+    }
+    fn part1(expressions: &Self::Input) -> usize {
+        expressions.iter().map(|e| eval_part1(0, e).1).sum()
+    }
+    fn part2(expressions: &Self::Input) -> usize {
+        expressions.iter().map(|e| eval_precedence(e)).sum()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::fs;
 
-    use super::{do_part2, eval_part1};
+    use super::{check_expression, do_part2, eval_part1, parse_expressions, Day18};
+    use crate::solution::Solution;
     #[test]
     fn example() {
         let input: Vec<_> = "1 + (2 * 3) + (4 * (5 + 6))".chars().collect();
@@ -125,7 +211,26 @@ mod tests {
     #[test]
     fn part2() {
         let input = fs::read_to_string("input/day18").unwrap();
-        let ans: usize = input.lines().map(|l| do_part2(l)).sum();
+        let ans: usize = input.lines().map(do_part2).sum();
         assert_eq!(ans, 119224703255966);
     }
+    #[test]
+    fn bad_expression() {
+        assert!(check_expression(0, "1 + (2 * 3) + (4 * (5 + 6))").is_ok());
+        let err = check_expression(0, "1 + (2 * 3)) + 4").unwrap_err();
+        assert_eq!((err.column, err.text.as_str()), (12, ")"));
+        let err = check_expression(0, "1 + (2 * 3").unwrap_err();
+        assert_eq!((err.column, err.expected.as_str()), (11, "`)`"));
+        let err = check_expression(0, "12 + 3").unwrap_err();
+        assert_eq!(err.column, 2);
+        let err = parse_expressions("1 + 2\n3 *").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 4));
+    }
+    #[test]
+    fn expressions() {
+        let expressions = Day18::parse("2 * 3 + (4 * 5)\n5 + (8 * 3 + 9 + 3 * 4 * 3)").unwrap();
+        assert_eq!(expressions[0].iter().collect::<String>(), "2*3+(4*5)");
+        assert_eq!(Day18::part1(&expressions), 26 + 437);
+        assert_eq!(Day18::part2(&expressions), 46 + 1445);
+    }
 }
@@ -1,44 +1,76 @@
+//! Day 19: Monster Messages.
+use crate::parse::{number, sections, ParseError};
+use crate::solution::Solution;
 use std::{
     collections::{HashMap, HashSet},
     iter,
 };
-#[derive(Debug)]
-enum Rule {
+/// A message rule.
+#[derive(Clone, Debug)]
+pub enum Rule {
+    /// Matches this text.
     Match(String),
+    /// Matches the rules with these ids in turn.
     Subrule(Vec<usize>),
+    /// Matches either list of rules in turn.
     OrSubRules(Vec<usize>, Vec<usize>),
 }
 
-fn parse(input: &str) -> (HashMap<usize, Rule>, Vec<String>) {
-    let (rules, msgs) = input.split_once("\r\n\r\n").unwrap();
-    let scan_rule = |rule: &str| {
-        rule.split_whitespace()
-            .map(|s| s.parse().unwrap())
-            .collect::<Vec<usize>>()
+/// Parses the rules by id, and the messages.
+pub fn parse(input: &str) -> Result<(HashMap<usize, Rule>, Vec<String>), ParseError> {
+    let (rules, msgs) = match sections(input)[..] {
+        [(_, rules), (_, msgs), ..] => (rules, msgs),
+        _ => return Err(ParseError::end(input, "a blank line and the messages")),
     };
-    let rules = rules
-        .lines()
-        .map(|l| {
-            let (id, rule_str) = l.split_once(": ").unwrap();
+    let rule_lines: Vec<_> = rules.lines().enumerate().collect();
+    let rules = rule_lines
+        .iter()
+        .map(|&(i, l)| {
+            let scan_rule = |rule: &str| {
+                rule.split_whitespace()
+                    .map(|s| number(i, l, s))
+                    .collect::<Result<Vec<usize>, _>>()
+            };
+            let (id, rule_str) = l
+                .split_once(": ")
+                .ok_or_else(|| ParseError::at(i, l, l, "`<id>: <rule>`"))?;
             let rule = if rule_str.contains('|') {
-                let (rule1, rule2) = rule_str.split_once(" | ").unwrap();
-                let r1 = scan_rule(rule1);
-                let r2 = scan_rule(rule2);
+                let (rule1, rule2) = rule_str
+                    .split_once(" | ")
+                    .ok_or_else(|| ParseError::at(i, l, rule_str, "`<rules> | <rules>`"))?;
+                let r1 = scan_rule(rule1)?;
+                let r2 = scan_rule(rule2)?;
                 Rule::OrSubRules(r1, r2)
             } else if rule_str.contains('"') {
                 Rule::Match(rule_str.trim_matches('"').to_string())
             } else {
-                let r = scan_rule(rule_str);
+                let r = scan_rule(rule_str)?;
                 Rule::Subrule(r)
             };
-            (id.parse().unwrap(), rule)
+            Ok((number(i, l, id)?, rule))
         })
-        .collect();
+        .collect::<Result<HashMap<usize, Rule>, _>>()?;
+    if !rules.contains_key(&0) {
+        return Err(ParseError::end(input, "a rule 0"));
+    }
+    // Every referenced rule must exist.
+    for &(i, l) in &rule_lines {
+        let (_, rule_str) = l.split_once(": ").unwrap();
+        for s in rule_str.split_whitespace().filter(|&s| s != "|") {
+            if let Ok(id) = s.parse() {
+                if !rules.contains_key(&id) {
+                    return Err(ParseError::at(i, l, s, "a defined rule"));
+                }
+            }
+        }
+    }
     let msgs = msgs.lines().map(str::to_string).collect();
 
-    (rules, msgs)
+    Ok((rules, msgs))
 }
-fn check_rule<'a>(s: &'a str, r: &Rule, rule_map: &HashMap<usize, Rule>) -> HashSet<&'a str> {
+/// Matches `r` against the start of `s`, returning every possible remainder.
+/// `s` matches fully if the result contains "".
+pub fn check_rule<'a>(s: &'a str, r: &Rule, rule_map: &HashMap<usize, Rule>) -> HashSet<&'a str> {
     match r {
         Rule::Match(m) => s
             .strip_prefix(m)
@@ -74,6 +106,36 @@ fn check_rule<'a>(s: &'a str, r: &Rule, rule_map: &HashMap<usize, Rule>) -> Hash
     }
 }
 
+fn count_matching(rule_map: &HashMap<usize, Rule>, messages: &[String]) -> usize {
+    messages
+        .iter()
+        .map(|msg| check_rule(msg, &rule_map[&0], rule_map))
+        .filter(|res| res.contains(""))
+        .count()
+}
+
+/// Solver for day 19.
+pub struct Day19;
+
+impl Solution for Day19 {
+    type Input = (HashMap<usize, Rule>, Vec<String>);
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+    fn part1((rule_map, messages): &Self::Input) -> usize {
+        count_matching(rule_map, messages)
+    }
+    fn part2((rule_map, messages): &Self::Input) -> usize {
+        let mut rule_map = rule_map.clone();
+        rule_map.insert(8, Rule::OrSubRules(vec![42], vec![42, 8]));
+        rule_map.insert(11, Rule::OrSubRules(vec![42, 31], vec![42, 11, 31]));
+        count_matching(&rule_map, messages)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs;
@@ -82,7 +144,7 @@ mod tests {
     #[test]
     fn example() {
         let input = fs::read_to_string("input/example19").unwrap();
-        let (rule_map, messages) = parse(&input);
+        let (rule_map, messages) = parse(&input).unwrap();
         let ans = messages
             .iter()
             .map(|msg| check_rule(msg, &rule_map[&0], &rule_map))
@@ -93,7 +155,7 @@ mod tests {
     #[test]
     fn part1() {
         let input = fs::read_to_string("input/day19").unwrap();
-        let (rule_map, messages) = parse(&input);
+        let (rule_map, messages) = parse(&input).unwrap();
         let ans = messages
             .iter()
             .map(|msg| check_rule(msg, &rule_map[&0], &rule_map))
@@ -105,7 +167,7 @@ mod tests {
     #[test]
     fn example_part2() {
         let input = fs::read_to_string("input/example19_part2").unwrap();
-        let (mut rule_map, messages) = parse(&input);
+        let (mut rule_map, messages) = parse(&input).unwrap();
         rule_map.insert(8, Rule::OrSubRules(vec![42], vec![42, 8]));
         rule_map.insert(11, Rule::OrSubRules(vec![42, 31], vec![42, 11, 31]));
         let ans = messages
@@ -118,7 +180,7 @@ mod tests {
     #[test]
     fn part2() {
         let input = fs::read_to_string("input/day19").unwrap();
-        let (mut rule_map, messages) = parse(&input);
+        let (mut rule_map, messages) = parse(&input).unwrap();
         rule_map.insert(8, Rule::OrSubRules(vec![42], vec![42, 8]));
         rule_map.insert(11, Rule::OrSubRules(vec![42, 31], vec![42, 11, 31]));
         let ans = messages
@@ -1,127 +1,289 @@
+//! Day 20: Jurassic Jigsaw.
+use crate::grid::Grid;
+use crate::parse::{sections, ParseError};
+use crate::pattern::Pattern;
+use crate::solution::Solution;
 use scan_fmt::scan_fmt;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
 
 #[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
-struct SideId(u64);
+/// Identifies a tile edge regardless of which way it is read.
+pub struct SideId(u64);
 
+/// A tile's number, from its `Tile <id>:` header.
 #[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
-struct TileId(u64);
+pub struct TileId(pub u64);
 
-type SideMap = HashMap<SideId, Vec<TileId>>;
+/// The tiles having each edge.
+pub type SideMap = HashMap<SideId, Vec<TileId>>;
 
-#[derive(Copy, Clone)]
-enum Orientation {
+/// Where a tile's original top edge points, after turning it clockwise.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Orientation {
+    /// Not turned.
     Twelve,
+    /// Turned a quarter clockwise.
     Three,
+    /// Turned half way.
     Six,
+    /// Turned three quarters clockwise.
     Nine,
 }
 
-#[derive(Copy, Clone)]
-enum Flipped {
+impl Orientation {
+    /// Every orientation, in order of quarter turns.
+    pub const ALL: [Orientation; 4] = [
+        Orientation::Twelve,
+        Orientation::Three,
+        Orientation::Six,
+        Orientation::Nine,
+    ];
+
+    fn quarter_turns(self) -> usize {
+        self as usize
+    }
+}
+
+/// Whether a tile or edge is mirrored.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Flipped {
+    /// Mirrored.
     True,
+    /// As read.
     False,
 }
 
-struct Tile {
+/// A tile edge, read left to right for the top and bottom edges and top to
+/// bottom for the left and right ones. Two tiles fit together along an
+/// edge when their touching edges are equal.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct Edge {
+    forward: u64,
+    backward: u64,
+}
+
+/// The longest side an [`Edge`] can hold, one bit per pixel.
+pub const MAX_SIDE: usize = 64;
+
+impl Edge {
+    /// Panics if `side` is longer than [`MAX_SIDE`].
+    pub fn new(side: &[bool]) -> Self {
+        assert!(side.len() <= MAX_SIDE, "edges are at most {} pixels", MAX_SIDE);
+        let bits = |acc, b: &bool| acc << 1 | *b as u64;
+        Edge {
+            forward: side.iter().fold(0, bits),
+            backward: side.iter().rev().fold(0, bits),
+        }
+    }
+    /// The id shared by both readings of the edge.
+    pub fn id(self) -> SideId {
+        SideId(self.forward.min(self.backward))
+    }
+    /// Whether this reading is the reverse of the one the id comes from.
+    pub fn flipped(self) -> Flipped {
+        if self.backward < self.forward {
+            Flipped::True
+        } else {
+            Flipped::False
+        }
+    }
+    /// The edge read the other way.
+    pub fn reversed(self) -> Self {
+        Edge {
+            forward: self.backward,
+            backward: self.forward,
+        }
+    }
+}
+
+/// An image tile.
+#[derive(Clone, Debug)]
+pub struct Tile {
     id: TileId,
-    map: Vec<Vec<bool>>,
-    side_ids: [(SideId, Flipped); 4], //< bool is true if side is flipped
+    map: Grid<bool>,
+    /// Top, right, bottom and left.
+    edges: [Edge; 4],
 }
 
 impl Tile {
-    fn parse(input: &str) -> Self {
-        let mut lines = input.lines();
-        let id: u64 = scan_fmt!(lines.next().unwrap(), "Tile {}:", u64).unwrap();
-        let id = TileId(id);
-        let mut map = Vec::new();
-        for line in lines {
-            let row: Vec<_> = line
-                .chars()
-                .map(|c| match c {
-                    '#' => true,
-                    '.' => false,
-                    _ => panic!(),
-                })
-                .collect();
-            map.push(row);
-        }
-        let top = map.first().unwrap();
-        let bottom = map.last().unwrap();
-        let left: Vec<_> = map.iter().map(|row| row[0]).collect();
-        let right: Vec<_> = map.iter().map(|row| *row.last().unwrap()).collect();
-        let side_ids = [
-            calculate_side_id(top),
-            calculate_side_id(&right),
-            calculate_side_id(bottom),
-            calculate_side_id(&left),
+    /// Panics if `map` is not square.
+    fn new(id: TileId, map: Grid<bool>) -> Self {
+        assert_eq!(map.width(), map.height(), "tiles are square");
+        let top = map.rows().next().unwrap();
+        let bottom = map.rows().last().unwrap();
+        let left: Vec<_> = map.column(0).copied().collect();
+        let right: Vec<_> = map.column(map.width() - 1).copied().collect();
+        let edges = [
+            Edge::new(top),
+            Edge::new(&right),
+            Edge::new(bottom),
+            Edge::new(&left),
         ];
-        Self { id, map, side_ids }
+        Tile { id, map, edges }
+    }
+
+    /// Parses a square tile with its `Tile <id>:` header. Line numbers in
+    /// errors count from the header.
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let (header, rows) = input.split_once('\n').unwrap_or((input, ""));
+        let header = header.trim_end();
+        let id: u64 = scan_fmt!(header, "Tile {}:", u64)
+            .map_err(|_| ParseError::at(0, header, header, "`Tile <id>:`"))?;
+        let map = Grid::parse(rows, "`.` or `#`", |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })
+        .map_err(|e| e.offset(1))?;
+        let first = rows.lines().next().unwrap_or("").trim_end();
+        if map.width() < 3 {
+            return Err(ParseError::new(1, first.len(), "", "at least 3 pixels"));
+        }
+        if map.width() > MAX_SIDE {
+            let expected = format!("at most {} pixels", MAX_SIDE);
+            return Err(ParseError::at(1, first, &first[MAX_SIDE..], expected));
+        }
+        if map.width() != map.height() {
+            let expected = format!("{} rows", map.width());
+            return Err(ParseError::end(input, expected));
+        }
+        Ok(Tile::new(TileId(id), map))
     }
-    fn get_opposite_side_id(&self, s_id: &SideId) -> (SideId, Flipped) {
-        let pos = self
-            .side_ids
-            .iter()
-            .position(|(self_s_id, _)| self_s_id == s_id)
-            .expect("invalid side id");
-        self.side_ids[(pos + 2) % 4]
+    /// The tile's number.
+    pub fn id(&self) -> TileId {
+        self.id
+    }
+    /// The tile's pixels, as currently turned.
+    pub fn map(&self) -> &Grid<bool> {
+        &self.map
     }
-}
 
-fn calculate_side_id(side: &[bool]) -> (SideId, Flipped) {
-    let s_id = side
-        .iter()
-        .fold(0, |acc, b| if *b { (acc << 1) + 1 } else { acc << 1 });
+    /// The top edge.
+    pub fn top(&self) -> Edge {
+        self.edges[0]
+    }
+    /// The right edge.
+    pub fn right(&self) -> Edge {
+        self.edges[1]
+    }
+    /// The bottom edge.
+    pub fn bottom(&self) -> Edge {
+        self.edges[2]
+    }
+    /// The left edge.
+    pub fn left(&self) -> Edge {
+        self.edges[3]
+    }
 
-    let rev_id = side
-        .iter()
-        .rev()
-        .fold(0, |acc, b| if *b { (acc << 1) + 1 } else { acc << 1 });
-    if rev_id < s_id {
-        (SideId(rev_id), Flipped::True)
-    } else {
-        (SideId(rev_id), Flipped::False)
+    /// The tile turned a quarter clockwise.
+    pub fn rotate(&self) -> Self {
+        Tile::new(self.id, self.map.rotate())
     }
+    /// The tile mirrored left to right.
+    pub fn flip(&self) -> Self {
+        Tile::new(self.id, self.map.flip())
+    }
+    /// The tile flipped if `flip` says so, then turned to `rot`.
+    pub fn orient(&self, rot: Orientation, flip: Flipped) -> Self {
+        let mut tile = match flip {
+            Flipped::True => self.flip(),
+            Flipped::False => self.clone(),
+        };
+        for _ in 0..rot.quarter_turns() {
+            tile = tile.rotate();
+        }
+        tile
+    }
+}
+
+/// Parses the tiles, separated by blank lines.
+pub fn parse_tiles(input: &str) -> Result<Vec<Tile>, ParseError> {
+    sections(input)
+        .into_iter()
+        .map(|(first, s)| Tile::parse(s).map_err(|e| e.offset(first)))
+        .collect()
 }
 
-fn map_sides(tiles: &mut dyn Iterator<Item = &Tile>) -> SideMap {
+/// The tiles having each edge, read either way.
+pub fn map_sides(tiles: &mut dyn Iterator<Item = &Tile>) -> SideMap {
     let mut side_map = SideMap::new();
     for tile in tiles {
-        for (si, _) in tile.side_ids {
-            side_map.entry(si).or_default().push(tile.id);
+        for edge in tile.edges {
+            side_map.entry(edge.id()).or_default().push(tile.id);
         }
     }
     side_map
 }
 
-fn find_corner_tiles(side_map: &SideMap) -> Vec<TileId> {
+/// Finds the tiles with two edges that match no other tile, by id.
+pub fn find_corner_tiles(side_map: &SideMap) -> Vec<TileId> {
     let mut open_side_counts = HashMap::<TileId, u8>::new();
     for tiles in side_map.values() {
         if tiles.len() == 1 {
             *open_side_counts.entry(tiles[0]).or_default() += 1;
         }
     }
-    open_side_counts
+    let mut corners: Vec<_> = open_side_counts
         .iter()
         .filter(|(_id, count)| **count == 2)
         .map(|(id, _)| id)
         .cloned()
-        .collect()
+        .collect();
+    corners.sort_unstable_by_key(|id| id.0);
+    corners
 }
 
-#[derive(Copy, Clone)]
+/// A tile placed in the image, turned and possibly flipped.
+#[derive(Copy, Clone, Debug)]
 struct TileDescription {
     id: TileId,
     rot: Orientation,
     flip: Flipped,
 }
 
+impl TileDescription {
+    /// The eight ways to place tile `id`.
+    fn all(id: TileId) -> impl Iterator<Item = TileDescription> {
+        [Flipped::False, Flipped::True].iter().flat_map(move |&flip| {
+            Orientation::ALL
+                .iter()
+                .map(move |&rot| TileDescription { id, rot, flip })
+        })
+    }
+
+    /// The tile as placed.
+    fn tile(&self, tiles: &HashMap<TileId, Tile>) -> Tile {
+        tiles[&self.id].orient(self.rot, self.flip)
+    }
+}
+
+/// Whether no other tile has `edge`.
+fn is_open(edge: Edge, side_map: &SideMap) -> bool {
+    side_map[&edge.id()].len() == 1
+}
+
+/// Places the tile sharing `edge` with tile `current` so that `side` of it
+/// is `edge`.
+fn find_next(
+    current: TileId,
+    edge: Edge,
+    side: fn(&Tile) -> Edge,
+    tiles: &HashMap<TileId, Tile>,
+    side_map: &SideMap,
+) -> Option<TileDescription> {
+    let next = *side_map[&edge.id()].iter().find(|&&id| id != current)?;
+    TileDescription::all(next).find(|d| side(&d.tile(tiles)) == edge)
+}
+
 fn find_next_right(
     current: &TileDescription,
     tiles: &HashMap<TileId, Tile>,
     side_map: &SideMap,
 ) -> Option<TileDescription> {
-    todo!()
+    let edge = current.tile(tiles).right();
+    find_next(current.id, edge, Tile::left, tiles, side_map)
 }
 
 fn find_next_down(
@@ -129,22 +291,23 @@ fn find_next_down(
     tiles: &HashMap<TileId, Tile>,
     side_map: &SideMap,
 ) -> Option<TileDescription> {
-    todo!()
+    let edge = current.tile(tiles).bottom();
+    find_next(current.id, edge, Tile::top, tiles, side_map)
 }
 
-#[allow(unused_variables, unused_mut)]
+/// Lays out the tiles row by row from the corner `start_tile`, turned so
+/// that its open edges are at the top and left. None if the tiles do not
+/// form a full rectangle that way.
 fn assemble_map(
     tiles: &HashMap<TileId, Tile>,
     side_map: &SideMap,
     start_tile: TileId,
-) -> Vec<Vec<TileDescription>> {
-    //start in one of the corners, build row by row.
-    let mut result = Vec::new();
-    let mut next_down = Some(TileDescription {
-        id: start_tile,
-        rot: Orientation::Twelve,
-        flip: Flipped::False,
+) -> Option<Vec<Vec<TileDescription>>> {
+    let mut next_down = TileDescription::all(start_tile).find(|d| {
+        let tile = d.tile(tiles);
+        is_open(tile.top(), side_map) && is_open(tile.left(), side_map)
     });
+    let mut result: Vec<Vec<TileDescription>> = Vec::new();
     while let Some(first) = next_down {
         let mut row = vec![first];
         let mut next_right = find_next_right(&first, tiles, side_map);
@@ -152,46 +315,551 @@ fn assemble_map(
             row.push(next);
             next_right = find_next_right(&next, tiles, side_map);
         }
+        if result.first().is_some_and(|r| r.len() != row.len()) {
+            return None;
+        }
         result.push(row);
         next_down = find_next_down(&first, tiles, side_map);
     }
+    let placed = result.iter().map(Vec::len).sum::<usize>();
+    (placed == tiles.len()).then_some(result)
+}
+
+/// Joins the placed tiles into one image, without their borders.
+pub fn join_tiles(layout: &[Vec<Tile>]) -> Grid<bool> {
+    let inner = layout[0][0].map.width() - 2;
+    let (width, height) = (layout[0].len() * inner, layout.len() * inner);
+    Grid::from_fn(width, height, |(x, y)| {
+        layout[y / inner][x / inner].map[(x % inner + 1, y % inner + 1)]
+    })
+}
+
+/// Why tiles could not be assembled into an image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AssemblyError {
+    /// The number of tiles is not a square.
+    NotSquare(usize),
+    /// The tiles are not all the same size.
+    MixedSizes,
+    /// No arrangement matches every pair of touching edges.
+    NoArrangement,
+}
+
+impl fmt::Display for AssemblyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssemblyError::NotSquare(n) => {
+                write!(f, "{} tiles cannot form a square image", n)
+            }
+            AssemblyError::MixedSizes => write!(f, "tiles differ in size"),
+            AssemblyError::NoArrangement => {
+                write!(f, "no arrangement of the tiles matches all edges")
+            }
+        }
+    }
+}
+
+impl Error for AssemblyError {}
+
+/// Fills an `n` by `n` layout row by row, trying every unused tile in every
+/// orientation that fits its left and top neighbors, and undoing placements
+/// that lead to a dead end.
+struct Backtrack<'a> {
+    n: usize,
+    /// The eight placements of each tile.
+    placements: HashMap<TileId, Vec<Tile>>,
+    side_map: &'a SideMap,
+    /// Tiles to try in the top left corner, likely corners first.
+    starts: Vec<TileId>,
+    layout: Vec<Tile>,
+    used: HashSet<TileId>,
+}
+
+impl Backtrack<'_> {
+    fn place(&mut self) -> bool {
+        let pos = self.layout.len();
+        if pos == self.n * self.n {
+            return true;
+        }
+        let left = (!pos.is_multiple_of(self.n)).then(|| self.layout[pos - 1].right());
+        let above = (pos >= self.n).then(|| self.layout[pos - self.n].bottom());
+        let mut candidates = match left.or(above) {
+            Some(edge) => self.side_map[&edge.id()].clone(),
+            None => self.starts.clone(),
+        };
+        candidates.dedup();
+        for id in candidates {
+            if self.used.contains(&id) {
+                continue;
+            }
+            for i in 0..8 {
+                let tile = &self.placements[&id][i];
+                if left.is_some_and(|e| tile.left() != e)
+                    || above.is_some_and(|e| tile.top() != e)
+                {
+                    continue;
+                }
+                self.layout.push(tile.clone());
+                self.used.insert(id);
+                if self.place() {
+                    return true;
+                }
+                self.layout.pop();
+                self.used.remove(&id);
+            }
+        }
+        false
+    }
+}
+
+/// Finds an arrangement by backtracking, for when edges do not identify
+/// neighbors uniquely.
+fn backtrack(
+    tiles: &HashMap<TileId, Tile>,
+    side_map: &SideMap,
+    n: usize,
+) -> Option<Vec<Vec<Tile>>> {
+    let open = |tile: &Tile| {
+        tile.edges.iter().filter(|&&e| is_open(e, side_map)).count()
+    };
+    let mut starts: Vec<_> = tiles.keys().copied().collect();
+    starts.sort_by_key(|id| (usize::MAX - open(&tiles[id]), id.0));
+    let placements = tiles
+        .keys()
+        .map(|&id| {
+            let all = TileDescription::all(id).map(|d| d.tile(tiles)).collect();
+            (id, all)
+        })
+        .collect();
+    let mut search = Backtrack {
+        n,
+        placements,
+        side_map,
+        starts,
+        layout: Vec::new(),
+        used: HashSet::new(),
+    };
+    if !search.place() {
+        return None;
+    }
+    Some(search.layout.chunks(n).map(<[Tile]>::to_vec).collect())
+}
+
+/// The tiles as placed in the image, row by row. When every edge is shared
+/// by at most two tiles and there are exactly four corners, each tile's
+/// neighbors follow from its edges, starting from the corner with the
+/// smallest id; otherwise the arrangement is searched for by backtracking.
+pub fn assemble(tiles: Vec<Tile>) -> Result<Vec<Vec<Tile>>, AssemblyError> {
+    let n = (0..).find(|n| n * n >= tiles.len()).unwrap();
+    if n == 0 || n * n != tiles.len() {
+        return Err(AssemblyError::NotSquare(tiles.len()));
+    }
+    if tiles.iter().any(|t| t.map.width() != tiles[0].map.width()) {
+        return Err(AssemblyError::MixedSizes);
+    }
+    let tiles: HashMap<_, _> = tiles.into_iter().map(|t| (t.id, t)).collect();
+    let side_map = map_sides(&mut tiles.values());
+    let corners = find_corner_tiles(&side_map);
+    if corners.len() == 4 && side_map.values().all(|t| t.len() <= 2) {
+        let layout = assemble_map(&tiles, &side_map, corners[0])
+            .filter(|rows| rows.len() == n);
+        if let Some(layout) = layout {
+            return Ok(layout
+                .iter()
+                .map(|row| row.iter().map(|d| d.tile(&tiles)).collect())
+                .collect());
+        }
+    }
+    backtrack(&tiles, &side_map, n).ok_or(AssemblyError::NoArrangement)
+}
+
+/// Assembles the tiles into the full image, without tile borders.
+pub fn assemble_image(tiles: Vec<Tile>) -> Result<Grid<bool>, AssemblyError> {
+    assemble(tiles).map(|layout| join_tiles(&layout))
+}
+
+/// The sea monster, as drawn in the puzzle.
+pub const SEA_MONSTER: &str = "                  # \n\
+                               #    ##    ##    ###\n\
+                               \x20#  #  #  #  #  #   ";
+
+/// Number of `#` cells of `image` not covered by `pattern`, looking for it
+/// in every orientation.
+pub fn roughness(image: &Grid<bool>, pattern: &Pattern) -> usize {
+    let covered = pattern.search(image, |&b| b).covered;
+    image.iter().filter(|&&b| b).count() - covered.len()
+}
+
+/// Number of `#` cells that are not part of a sea monster.
+pub fn water_roughness(image: &Grid<bool>) -> usize {
+    roughness(image, &Pattern::parse(SEA_MONSTER).unwrap())
+}
+
+/// The cells of `image` that are part of a sea monster.
+pub fn sea_monsters(image: &Grid<bool>) -> HashSet<(usize, usize)> {
+    let monster = Pattern::parse(SEA_MONSTER).unwrap();
+    monster.search(image, |&b| b).covered
+}
+
+/// How a pixel is drawn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Pixel {
+    Water,
+    Rough,
+    Monster,
+    /// A `#` on a tile border.
+    Border,
+}
 
-    result
+impl Pixel {
+    fn color(&self) -> [u8; 3] {
+        match self {
+            Pixel::Water => [20, 50, 100],
+            Pixel::Rough => [120, 180, 230],
+            Pixel::Monster => [40, 200, 70],
+            Pixel::Border => [130, 130, 130],
+        }
+    }
+}
+
+fn image_pixels(
+    image: &Grid<bool>,
+    monsters: &HashSet<(usize, usize)>,
+) -> Grid<Pixel> {
+    Grid::from_fn(image.width(), image.height(), |pos| {
+        match (image[pos], monsters.contains(&pos)) {
+            (true, true) => Pixel::Monster,
+            (true, false) => Pixel::Rough,
+            (false, _) => Pixel::Water,
+        }
+    })
+}
+
+/// Draws the image as a binary PPM, `scale` pixels per cell, with the
+/// `monsters` cells highlighted.
+pub fn image_ppm(
+    image: &Grid<bool>,
+    monsters: &HashSet<(usize, usize)>,
+    scale: usize,
+) -> Vec<u8> {
+    image_pixels(image, monsters).to_ppm(scale, Pixel::color)
+}
+
+/// One square per `#` pixel, `scale` units wide, with the top left of the
+/// grid at `(left, top)`.
+fn svg_pixels(
+    svg: &mut String,
+    pixels: &Grid<Pixel>,
+    (left, top): (usize, usize),
+    scale: usize,
+) {
+    for ((x, y), pixel) in pixels.positions() {
+        if *pixel != Pixel::Water {
+            let [r, g, b] = pixel.color();
+            svg.push_str(&format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{s}\" height=\"{s}\" \
+                 fill=\"#{:02x}{:02x}{:02x}\"/>\n",
+                left + x * scale,
+                top + y * scale,
+                r,
+                g,
+                b,
+                s = scale,
+            ));
+        }
+    }
 }
+
+fn svg_document(width: usize, height: usize, body: &str) -> String {
+    let [r, g, b] = Pixel::Water.color();
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" \
+         viewBox=\"0 0 {w} {h}\" shape-rendering=\"crispEdges\">\n\
+         <rect width=\"{w}\" height=\"{h}\" fill=\"#{:02x}{:02x}{:02x}\"/>\n{}</svg>\n",
+        r,
+        g,
+        b,
+        body,
+        w = width,
+        h = height,
+    )
+}
+
+/// Draws the image as SVG, `scale` units per cell, with the `monsters`
+/// cells highlighted.
+pub fn image_svg(
+    image: &Grid<bool>,
+    monsters: &HashSet<(usize, usize)>,
+    scale: usize,
+) -> String {
+    let mut body = String::new();
+    svg_pixels(&mut body, &image_pixels(image, monsters), (0, 0), scale);
+    svg_document(image.width() * scale, image.height() * scale, &body)
+}
+
+/// Draws the placed tiles as SVG, `scale` units per pixel, spaced apart
+/// and labelled with their ids. Borders are drawn in gray and `monsters`,
+/// given in the coordinates of the joined image, are highlighted.
+pub fn tiles_svg(
+    layout: &[Vec<Tile>],
+    monsters: &HashSet<(usize, usize)>,
+    scale: usize,
+) -> String {
+    let size = layout[0][0].map.width();
+    let inner = size - 2;
+    let label = 2 * scale;
+    let step = (size + 1) * scale;
+    let mut body = String::new();
+    for (row, tiles) in layout.iter().enumerate() {
+        for (col, tile) in tiles.iter().enumerate() {
+            let (left, top) = (col * step + scale, row * (step + label) + scale);
+            body.push_str(&format!(
+                "<text x=\"{}\" y=\"{}\" font-size=\"{}\" fill=\"white\">{}</text>\n",
+                left,
+                top + label - scale / 2,
+                label - scale / 2,
+                tile.id.0,
+            ));
+            let pixels = Grid::from_fn(size, size, |(x, y)| {
+                let border = x == 0 || y == 0 || x == size - 1 || y == size - 1;
+                let in_monster = || {
+                    let pos = (col * inner + x - 1, row * inner + y - 1);
+                    monsters.contains(&pos)
+                };
+                match (tile.map[(x, y)], border) {
+                    (false, _) => Pixel::Water,
+                    (true, true) => Pixel::Border,
+                    (true, false) if in_monster() => Pixel::Monster,
+                    (true, false) => Pixel::Rough,
+                }
+            });
+            svg_pixels(&mut body, &pixels, (left, top + label), scale);
+        }
+    }
+    let width = layout[0].len() * step + scale;
+    let height = layout.len() * (step + label) + scale;
+    svg_document(width, height, &body)
+}
+
+/// Solver for day 20.
+pub struct Day20;
+
+/// Points an assembly error at the tiles of `input` that caused it.
+fn assembly_parse_error(input: &str, tiles: &[Tile], err: AssemblyError) -> ParseError {
+    match err {
+        AssemblyError::MixedSizes => {
+            let width = tiles[0].map.width();
+            let (first, tile) = sections(input)
+                .into_iter()
+                .zip(tiles)
+                .find(|(_, t)| t.map.width() != width)
+                .map(|(s, _)| s)
+                .expect("tiles of mixed sizes");
+            let header = tile.lines().next().unwrap_or("");
+            ParseError::at(first, header, header, format!("a tile {} pixels wide", width))
+        }
+        err => ParseError::end(input, format!("tiles forming an image, but {}", err)),
+    }
+}
+
+impl Solution for Day20 {
+    /// The tiles as placed, and the image they assemble into.
+    type Input = (Vec<Vec<Tile>>, Grid<bool>);
+    type Output1 = u64;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let tiles = parse_tiles(input)?;
+        let layout =
+            assemble(tiles.clone()).map_err(|e| assembly_parse_error(input, &tiles, e))?;
+        let image = join_tiles(&layout);
+        Ok((layout, image))
+    }
+    fn part1((layout, _): &Self::Input) -> u64 {
+        let (top, bottom) = (&layout[0], &layout[layout.len() - 1]);
+        let corners = [&top[0], &top[top.len() - 1], &bottom[0], &bottom[bottom.len() - 1]];
+        corners.iter().map(|t| t.id().0).product()
+    }
+    fn part2((_, image): &Self::Input) -> usize {
+        water_roughness(image)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::day20::*;
+    use std::collections::HashSet;
     use std::fs;
     #[test]
+    fn bad_tile() {
+        let err = Tile::parse("Tile 7:\n#.\n.o").err().unwrap();
+        assert_eq!((err.line, err.column, err.text.as_str()), (3, 2, "o"));
+        let err = parse_tiles("Tile 1:\n#..\r\n.#.\r\n..#\r\n\r\nTile x:\n#").err().unwrap();
+        assert_eq!((err.line, err.column), (6, 1));
+        // Tiles have a border and something inside it.
+        let err = Tile::parse("Tile 7:\n#.\n.#").err().unwrap();
+        assert_eq!((err.line, err.column, err.expected.as_str()), (2, 3, "at least 3 pixels"));
+        assert!(Tile::parse("Tile 7:\n").is_err());
+        let wide = format!("Tile 7:\n{}", format!("{}\n", ".".repeat(65)).repeat(65));
+        let err = Tile::parse(&wide).err().unwrap();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 65, "."));
+    }
+    #[test]
+    fn orientations() {
+        let tile = Tile::parse("Tile 1:\n##.\n..#\n#..").unwrap();
+        let top = tile.top();
+        assert_eq!((top.id(), top.flipped()), (SideId(3), Flipped::True));
+        assert_eq!(tile.top().reversed().flipped(), Flipped::False);
+        let ids = |t: &Tile| {
+            let mut ids: Vec<_> = t.edges.iter().map(|e| e.id().0).collect();
+            ids.sort_unstable();
+            ids
+        };
+        let mut maps = HashSet::new();
+        for &flip in &[Flipped::False, Flipped::True] {
+            for &rot in &Orientation::ALL {
+                let t = tile.orient(rot, flip);
+                maps.insert(t.map().clone());
+                let left: Vec<_> = t.map().column(0).copied().collect();
+                assert_eq!(t.top(), Edge::new(t.map().rows().next().unwrap()));
+                assert_eq!(t.left(), Edge::new(&left));
+                assert_eq!(ids(&t), ids(&tile));
+                let r = t.rotate();
+                assert_eq!(
+                    [r.top(), r.right(), r.bottom(), r.left()],
+                    [t.left().reversed(), t.top(), t.right().reversed(), t.bottom()]
+                );
+                let f = t.flip();
+                assert_eq!(
+                    [f.top(), f.right(), f.bottom(), f.left()],
+                    [t.top().reversed(), t.left(), t.bottom().reversed(), t.right()]
+                );
+            }
+        }
+        assert_eq!(maps.len(), 8);
+    }
+    #[test]
     fn example() {
         let input = fs::read_to_string("input/example20").unwrap();
-        let tiles: Vec<_> = input.split("\r\n\r\n").map(|s| Tile::parse(s)).collect();
+        let tiles = parse_tiles(&input).unwrap();
         let map = map_sides(&mut tiles.iter());
         let ans = find_corner_tiles(&map);
+        let ids: Vec<_> = ans.iter().map(|id| id.0).collect();
+        assert_eq!(ids, [1171, 1951, 2971, 3079]);
         assert_eq!(ans.iter().fold(1, |acc, id| acc * id.0), 20899048083289);
     }
     #[test]
     fn part1() {
         let input = fs::read_to_string("input/day20").unwrap();
-        let tiles: Vec<_> = input.split("\r\n\r\n").map(|s| Tile::parse(s)).collect();
+        let tiles = parse_tiles(&input).unwrap();
         let map = map_sides(&mut tiles.iter());
         let ans = find_corner_tiles(&map);
         assert_eq!(ans.iter().fold(1, |acc, id| acc * id.0), 23386616781851);
     }
     #[test]
+    fn example_part2() {
+        let input = fs::read_to_string("input/example20").unwrap();
+        let image = assemble_image(parse_tiles(&input).unwrap()).unwrap();
+        assert_eq!((image.width(), image.height()), (24, 24));
+        assert_eq!(water_roughness(&image), 273);
+    }
+    #[test]
+    fn sea_monster() {
+        let monster = Pattern::parse(SEA_MONSTER).unwrap();
+        assert_eq!((monster.width(), monster.height()), (20, 3));
+        let input = fs::read_to_string("input/example20").unwrap();
+        let image = assemble_image(parse_tiles(&input).unwrap()).unwrap();
+        let search = monster.search(&image, |&b| b);
+        assert_eq!(search.matches.len(), 2);
+        assert_eq!(search.covered.len(), 30);
+    }
+    #[test]
+    fn render() {
+        let input = fs::read_to_string("input/example20").unwrap();
+        let layout = assemble(parse_tiles(&input).unwrap()).unwrap();
+        // The layout starts from the corner with the smallest id.
+        assert_eq!(layout[0][0].id().0, 1171);
+        let image = join_tiles(&layout);
+        let monsters = sea_monsters(&image);
+        assert_eq!(monsters.len(), 30);
+        let ppm = image_ppm(&image, &monsters, 2);
+        assert!(ppm.starts_with(b"P6\n48 48\n255\n"));
+        let svg = image_svg(&image, &monsters, 3);
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"72\""));
+        let rough = image.iter().filter(|&&b| b).count();
+        assert_eq!(svg.matches("<rect").count(), 1 + rough);
+        assert_eq!(svg.matches("fill=\"#28c846\"").count(), 30);
+        let svg = tiles_svg(&layout, &monsters, 1);
+        assert_eq!(svg.matches("<text").count(), 9);
+        assert!(svg.contains(">1951</text>"));
+        assert_eq!(svg.matches("fill=\"#28c846\"").count(), 30);
+    }
+    #[test]
+    fn ambiguous_edges() {
+        // A 3 by 3 puzzle of 5 pixel tiles cut from a random picture, with
+        // the top edge of the middle tile on top repeating its bottom edge.
+        let mut seed = 20u64;
+        let mut bit = || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+            seed >> 63 == 1
+        };
+        let mut picture = Grid::from_fn(13, 13, |_| bit());
+        for x in 4..=8 {
+            picture[(x, 0)] = picture[(x, 4)];
+        }
+        let mut input = String::new();
+        for id in 0..9 {
+            let (left, top) = (id % 3 * 4, id / 3 * 4);
+            input += &format!("Tile {}:\n", id + 1);
+            for y in top..=top + 4 {
+                let row = (left..=left + 4).map(|x| picture[(x, y)]);
+                input.extend(row.map(|b| if b { '#' } else { '.' }));
+                input.push('\n');
+            }
+            input.push('\n');
+        }
+        let tiles = parse_tiles(&input).unwrap();
+        let side_map = map_sides(&mut tiles.iter());
+        assert!(side_map.values().any(|t| t.len() > 2));
+        let layout = assemble(tiles).unwrap();
+        for (y, row) in layout.iter().enumerate() {
+            for (x, tile) in row.iter().enumerate() {
+                assert!(x == 0 || row[x - 1].right() == tile.left());
+                assert!(y == 0 || layout[y - 1][x].bottom() == tile.top());
+            }
+        }
+        let inner = |c: usize| c / 3 * 4 + c % 3 + 1;
+        let expected = Grid::from_fn(9, 9, |(x, y)| picture[(inner(x), inner(y))]);
+        assert!(expected.orientations().contains(&join_tiles(&layout)));
+        // Counting unmatched edges misses the corners here, the layout does not.
+        assert_ne!(find_corner_tiles(&side_map).len(), 4);
+        assert_eq!(Day20::part1(&Day20::parse(&input).unwrap()), 3 * 7 * 9);
+    }
+    #[test]
+    fn assembly_errors() {
+        let input = fs::read_to_string("input/example20").unwrap();
+        let mut tiles = parse_tiles(&input).unwrap();
+        let two = tiles[..2].to_vec();
+        assert_eq!(assemble(two).unwrap_err(), AssemblyError::NotSquare(2));
+        tiles[8] = Tile::parse("Tile 1:\n#..\n.#.\n..#").unwrap();
+        assert_eq!(assemble(tiles.clone()).unwrap_err(), AssemblyError::MixedSizes);
+        let blank = format!("Tile 1:\n{}", "..........\n".repeat(10));
+        tiles[8] = Tile::parse(&blank).unwrap();
+        let err = assemble(tiles).unwrap_err();
+        assert_eq!(err.to_string(), "no arrangement of the tiles matches all edges");
+        // The solution reports these as errors in the input.
+        let err = Day20::parse("").unwrap_err();
+        assert!(err.expected.ends_with("but 0 tiles cannot form a square image"));
+        let mut blocks: Vec<_> = input.split("\n\n").collect();
+        blocks[2] = "Tile 1:\n#..\n.#.\n..#";
+        let err = Day20::parse(&blocks.join("\n\n")).unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (25, 1, "Tile 1:"));
+        assert_eq!(err.expected, "a tile 10 pixels wide");
+    }
+    #[test]
     fn part2() {
         let input = fs::read_to_string("input/day20").unwrap();
-        let tile_map: HashMap<_, _> = input
-            .split("\r\n\r\n")
-            .map(|s| Tile::parse(s))
-            .map(|t| (t.id, t))
-            .collect();
-        let side_map = map_sides(&mut tile_map.values());
-        let ans = find_corner_tiles(&side_map);
-        let start_tile = ans[0];
-        //TODO: Implement this
-        let _big_map = assemble_map(&tile_map, &side_map, start_tile);
-        //TODO: turn into big map of bools and search for loch-ness
-        // TODO: Rotate/flip the monsta if no monsta was found.
+        let image = assemble_image(parse_tiles(&input).unwrap()).unwrap();
+        assert_eq!(water_roughness(&image), 2376);
     }
 }
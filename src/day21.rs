@@ -1,28 +1,36 @@
+//! Day 21: Allergen Assessment.
+use crate::parse::ParseError;
+use crate::solution::Solution;
 use std::collections::{HashMap, HashSet};
-struct Food {
+/// A food's ingredients and some of the allergens it contains.
+pub struct Food {
     ingredients: HashSet<String>,
     allergens: HashSet<String>,
 }
 
-fn parse(input: &str) -> Vec<Food> {
+/// Parses one food per line, like `a b (contains x, y)`.
+pub fn parse(input: &str) -> Result<Vec<Food>, ParseError> {
     //mxmxvkd kfcds sqjhc nhms (contains dairy, fish)
     input
         .lines()
-        .map(|l| {
-            let (ingredients, allergens) = l.split_once(" (contains ").unwrap();
+        .enumerate()
+        .map(|(i, l)| {
+            let (ingredients, allergens) = l
+                .split_once(" (contains ")
+                .ok_or_else(|| ParseError::at(i, l, &l[l.len()..], "` (contains `"))?;
+            let allergens = allergens
+                .strip_suffix(')')
+                .ok_or_else(|| ParseError::at(i, l, &l[l.len()..], "`)`"))?;
             let ingredients =
                 ingredients.split_whitespace().map(str::to_string).collect();
-            let allergens = allergens
-                .trim_end_matches(')')
-                .split(", ")
-                .map(str::to_string)
-                .collect();
-            Food { ingredients, allergens }
+            let allergens = allergens.split(", ").map(str::to_string).collect();
+            Ok(Food { ingredients, allergens })
         })
         .collect()
 }
 
-fn map_allergens(foods: &[Food]) -> HashMap<String, Vec<usize>> {
+/// Maps each allergen to the indices of the foods listing it.
+pub fn map_allergens(foods: &[Food]) -> HashMap<String, Vec<usize>> {
     let mut res: HashMap<String, Vec<usize>> = HashMap::new();
     for (i, f) in foods.iter().enumerate() {
         for a in &f.allergens {
@@ -31,7 +39,9 @@ fn map_allergens(foods: &[Food]) -> HashMap<String, Vec<usize>> {
     }
     res
 }
-fn find_allergy_solve(
+/// Works out which ingredient contains which allergen. Returns allergen by
+/// ingredient.
+pub fn find_allergy_solve(
     a_map: HashMap<String, Vec<usize>>,
     foods: &[Food],
 ) -> HashMap<String, String> {
@@ -58,6 +68,34 @@ fn find_allergy_solve(
     solved_map
 }
 
+/// Solver for day 21.
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Input = Vec<Food>;
+    type Output1 = usize;
+    type Output2 = String;
+
+    fn parse(input: &str) -> Result<Vec<Food>, ParseError> {
+        parse(input)
+    }
+    fn part1(foods: &Vec<Food>) -> usize {
+        let solved = find_allergy_solve(map_allergens(foods), foods);
+        foods
+            .iter()
+            .flat_map(|f| f.ingredients.iter())
+            .filter(|i| !solved.contains_key(*i))
+            .count()
+    }
+    fn part2(foods: &Vec<Food>) -> String {
+        let solved = find_allergy_solve(map_allergens(foods), foods);
+        let mut dangerous: Vec<_> = solved.iter().collect();
+        dangerous.sort_by_key(|&(_, allergy)| allergy);
+        let ans: Vec<&str> = dangerous.into_iter().map(|(i, _)| i.as_str()).collect();
+        ans.join(",")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs;
@@ -66,12 +104,12 @@ mod tests {
     #[test]
     fn example() {
         let input = fs::read_to_string("input/example21").unwrap();
-        let _foods = parse(&input);
+        let _foods = parse(&input).unwrap();
     }
     #[test]
     fn part1() {
         let input = fs::read_to_string("input/day21").unwrap();
-        let foods = parse(&input);
+        let foods = parse(&input).unwrap();
         let t = map_allergens(&foods);
         let solved = find_allergy_solve(t, &foods);
         let ans = foods
@@ -84,7 +122,7 @@ mod tests {
     #[test]
     fn part2() {
         let input = fs::read_to_string("input/day21").unwrap();
-        let foods = parse(&input);
+        let foods = parse(&input).unwrap();
         let t = map_allergens(&foods);
         let solved = find_allergy_solve(t, &foods);
         let mut dangerous: Vec<_> = solved.iter().collect();
@@ -1,30 +1,47 @@
+//! Day 22: Crab Combat.
+use crate::parse::{number, sections, ParseError};
+use crate::solution::Solution;
 use std::{
     collections::{hash_map::DefaultHasher, HashSet, VecDeque},
     hash::{Hash, Hasher},
 };
 
-fn parse_decks(input: &str) -> (VecDeque<usize>, VecDeque<usize>) {
-    let (p1, p2) = input.split_once("\r\n\r\n").unwrap();
-    let deck1 = p1.lines().skip(1).map(|l| l.parse().unwrap()).collect();
-    let deck2 = p2.lines().skip(1).map(|l| l.parse().unwrap()).collect();
-    (deck1, deck2)
+/// Parses the two players' decks, top card first.
+pub fn parse_decks(input: &str) -> Result<(VecDeque<usize>, VecDeque<usize>), ParseError> {
+    match sections(input)[..] {
+        [p1, p2] => Ok((parse_deck(p1)?, parse_deck(p2)?)),
+        _ => Err(ParseError::end(input, "two decks separated by a blank line")),
+    }
+}
+
+/// Parses the cards below a `Player <n>:` header.
+fn parse_deck((offset, deck): (usize, &str)) -> Result<VecDeque<usize>, ParseError> {
+    deck.lines()
+        .enumerate()
+        .skip(1)
+        .map(|(i, l)| number(offset + i, l, l))
+        .collect()
 }
 
 fn calc_score(deck: &VecDeque<usize>) -> usize {
     deck.iter()
         .rev()
         .enumerate()
-        .map(|(i, card)| (i + 1) as usize * card)
+        .map(|(i, card)| (i + 1) * card)
         .sum()
 }
 
+/// The player who won a game.
 #[derive(Debug)]
-enum Winner {
+pub enum Winner {
+    /// Player 1.
     P1,
+    /// Player 2.
     P2,
 }
 
-fn crab_combat(mut deck1: VecDeque<usize>, mut deck2: VecDeque<usize>) -> (Winner, usize) {
+/// Plays Combat. Returns the winner and their score.
+pub fn crab_combat(mut deck1: VecDeque<usize>, mut deck2: VecDeque<usize>) -> (Winner, usize) {
     loop {
         if deck1.is_empty() {
             return (Winner::P2, calc_score(&deck2));
@@ -44,7 +61,8 @@ fn crab_combat(mut deck1: VecDeque<usize>, mut deck2: VecDeque<usize>) -> (Winne
     }
 }
 
-fn recursive_combat(mut deck1: VecDeque<usize>, mut deck2: VecDeque<usize>) -> (Winner, usize) {
+/// Plays Recursive Combat. Returns the winner and their score.
+pub fn recursive_combat(mut deck1: VecDeque<usize>, mut deck2: VecDeque<usize>) -> (Winner, usize) {
     let mut history: HashSet<u64> = HashSet::new();
     // game loop
     loop {
@@ -85,6 +103,25 @@ fn recursive_combat(mut deck1: VecDeque<usize>, mut deck2: VecDeque<usize>) -> (
     }
 }
 
+/// Solver for day 22.
+pub struct Day22;
+
+impl Solution for Day22 {
+    type Input = (VecDeque<usize>, VecDeque<usize>);
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_decks(input)
+    }
+    fn part1((deck1, deck2): &Self::Input) -> usize {
+        crab_combat(deck1.clone(), deck2.clone()).1
+    }
+    fn part2((deck1, deck2): &Self::Input) -> usize {
+        recursive_combat(deck1.clone(), deck2.clone()).1
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs;
@@ -94,7 +131,7 @@ mod tests {
     #[test]
     fn example1() {
         let input = fs::read_to_string("input/example22").unwrap();
-        let (deck1, deck2) = parse_decks(&input);
+        let (deck1, deck2) = parse_decks(&input).unwrap();
         let ans = crab_combat(deck1, deck2);
         dbg!(ans);
     }
@@ -102,15 +139,24 @@ mod tests {
     #[test]
     fn example2() {
         let input = fs::read_to_string("input/example22").unwrap();
-        let (deck1, deck2) = parse_decks(&input);
+        let (deck1, deck2) = parse_decks(&input).unwrap();
         let ans = recursive_combat(deck1, deck2);
         dbg!(ans.1);
     }
 
+    #[test]
+    fn line_endings() {
+        let input = fs::read_to_string("input/example22").unwrap();
+        let crlf = input.replace('\n', "\r\n");
+        assert_eq!(parse_decks(&crlf).unwrap(), parse_decks(&input).unwrap());
+        let trimmed = parse_decks(input.trim_end()).unwrap();
+        assert_eq!(trimmed, parse_decks(&input).unwrap());
+    }
+
     #[test]
     fn part1() {
         let input = fs::read_to_string("input/day22").unwrap();
-        let (deck1, deck2) = parse_decks(&input);
+        let (deck1, deck2) = parse_decks(&input).unwrap();
         let ans = crab_combat(deck1, deck2);
         dbg!(ans);
     }
@@ -118,7 +164,7 @@ mod tests {
     #[test]
     fn part2() {
         let input = fs::read_to_string("input/day22").unwrap();
-        let (deck1, deck2) = parse_decks(&input);
+        let (deck1, deck2) = parse_decks(&input).unwrap();
         let ans = recursive_combat(deck1, deck2);
         assert_eq!(ans.1, 32534);
     }
@@ -1,19 +1,21 @@
+//! Day 23: Crab Cups.
+use crate::parse::ParseError;
+use crate::solution::Solution;
 use itertools::Itertools;
 
-// Adjacency list. If zeroth element is (7,2) then cup 1 is after cup 8 but before cup 3.
-type Adjaceny = (usize, usize);
-type AdjList = Vec<Adjaceny>;
+/// The zero-based cups before and after a cup. If the zeroth element is
+/// (7, 2), cup 1 is after cup 8 but before cup 3.
+pub type Adjaceny = (usize, usize);
+/// The neighbors of every cup, indexed by zero-based label.
+pub type AdjList = Vec<Adjaceny>;
 
 fn link_cups(before: usize, after: usize, cups: &mut AdjList) {
     cups[before].1 = after;
     cups[after].0 = before;
 }
 
-fn parse_cups_part1(input: &str) -> AdjList {
-    let labels = input
-        .chars()
-        .map(|c| c.to_digit(10).unwrap() as usize - 1)
-        .collect_vec();
+/// Links the zero-based cup labels into a circle.
+pub fn parse_cups_part1(labels: &[usize]) -> AdjList {
     let mut cups = vec![(0, 0); labels.len()];
     for (&a, &b) in labels.iter().tuple_windows() {
         link_cups(a, b, &mut cups)
@@ -52,7 +54,9 @@ fn print_cups(cups: &[Adjaceny]) -> String {
     result
 }
 
-fn cup_game(mut cups: AdjList, start: usize, moves: usize) -> String {
+/// Plays `moves` moves from the zero-based cup `start` and returns the labels
+/// after cup 1.
+pub fn cup_game(mut cups: AdjList, start: usize, moves: usize) -> String {
     let mut curr = start;
     for _ in 0..moves {
         shift_three_cups(curr, &mut cups);
@@ -62,11 +66,8 @@ fn cup_game(mut cups: AdjList, start: usize, moves: usize) -> String {
 }
 
 /// Part 2
-fn parse_cups_part2(input: &str) -> AdjList {
-    let labels = input
-        .chars()
-        .map(|c| c.to_digit(10).unwrap() as usize - 1)
-        .collect_vec();
+/// Links the zero-based cup labels, followed by cups up to one million.
+pub fn parse_cups_part2(labels: &[usize]) -> AdjList {
     let mut cups = vec![(0, 0); labels.len()];
     for (&a, &b) in labels.iter().tuple_windows() {
         link_cups(a, b, &mut cups);
@@ -78,7 +79,8 @@ fn parse_cups_part2(input: &str) -> AdjList {
     cups
 }
 
-fn cup_game_part2(mut cups: AdjList, start: usize, moves: usize) -> usize {
+/// Plays `moves` moves and multiplies the two labels after cup 1.
+pub fn cup_game_part2(mut cups: AdjList, start: usize, moves: usize) -> usize {
     let mut curr = start;
     for _ in 0..moves {
         shift_three_cups(curr, &mut cups);
@@ -89,13 +91,65 @@ fn cup_game_part2(mut cups: AdjList, start: usize, moves: usize) -> usize {
     (a + 1) * (b + 1)
 }
 
+/// Checks that the labels are the digits 1 to n, each once, with at least
+/// five cups so a move has somewhere to put the three it picks up.
+fn check_labels(input: &str) -> Result<(), ParseError> {
+    let n = input.chars().count();
+    if n < 5 {
+        return Err(ParseError::end(input, "at least five cups"));
+    }
+    let mut seen = [false; 10];
+    for (i, c) in input.chars().enumerate() {
+        match c.to_digit(10) {
+            Some(d) if d >= 1 && d as usize <= n && !seen[d as usize] => {
+                seen[d as usize] = true
+            }
+            _ => {
+                let expected = format!("a new label from 1 to {}", n);
+                return Err(ParseError::new(0, i, &c.to_string(), expected));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Parses the cup labels into zero-based cups, in order. The current cup at
+/// the start of the game is the first one.
+pub fn parse_labels(input: &str) -> Result<Vec<usize>, ParseError> {
+    let labels = input.trim();
+    check_labels(labels)?;
+    Ok(labels.chars().map(|c| c.to_digit(10).unwrap() as usize - 1).collect())
+}
+
+/// Solver for day 23.
+pub struct Day23;
+
+impl Solution for Day23 {
+    type Input = Vec<usize>;
+    type Output1 = String;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Vec<usize>, ParseError> {
+        parse_labels(input)
+    }
+    fn part1(labels: &Vec<usize>) -> String {
+        cup_game(parse_cups_part1(labels), labels[0], 100)
+    }
+    fn part2(labels: &Vec<usize>) -> usize {
+        cup_game_part2(parse_cups_part2(labels), labels[0], 10_000_000)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::day23::{cup_game, cup_game_part2, parse_cups_part1, parse_cups_part2};
+    use crate::day23::{
+        check_labels, cup_game, cup_game_part2, parse_cups_part1, parse_cups_part2,
+        parse_labels,
+    };
     #[test]
     fn example1() {
         let input = "389125467";
-        let cups = parse_cups_part1(input);
+        let cups = parse_cups_part1(&parse_labels(input).unwrap());
         let ans = cup_game(cups, 2, 100);
         assert_eq!(ans, "67384529");
     }
@@ -103,15 +157,23 @@ mod tests {
     #[test]
     fn part1() {
         let input = "327465189";
-        let cups = parse_cups_part1(input);
+        let cups = parse_cups_part1(&parse_labels(input).unwrap());
         let ans = cup_game(cups, 2, 100);
         assert_eq!(ans, "82934675");
     }
 
+    #[test]
+    fn bad_labels() {
+        let err = check_labels("3891254637").unwrap_err();
+        assert_eq!((err.column, err.text.as_str()), (9, "3"));
+        assert!(check_labels("3812").is_err());
+        assert_eq!(parse_labels("35142\n"), Ok(vec![2, 4, 0, 3, 1]));
+    }
+
     #[test]
     fn example2() {
         let input = "389125467";
-        let cups = parse_cups_part2(input);
+        let cups = parse_cups_part2(&parse_labels(input).unwrap());
         let ans = cup_game_part2(cups, 2, 10_000_000);
         assert_eq!(ans, 149245887792);
     }
@@ -119,7 +181,7 @@ mod tests {
     #[test]
     fn part2() {
         let input = "327465189";
-        let cups = parse_cups_part2(input);
+        let cups = parse_cups_part2(&parse_labels(input).unwrap());
         let ans = cup_game_part2(cups, 2, 10_000_000);
         assert_eq!(ans, 474600314018);
     }
@@ -1,5 +1,11 @@
-use std::collections::{HashMap, HashSet};
-type Pos = cgmath::Vector2<i32>;
+//! Day 24: Lobby Layout.
+use crate::automaton::{Automaton, Rule, Topology};
+use crate::parse::ParseError;
+use crate::solution::Solution;
+use std::collections::HashMap;
+/// Hex tile position in doubled coordinates: east is (2, 0), north-east
+/// is (1, 1).
+pub type Pos = cgmath::Vector2<i32>;
 
 enum Dir {
     E,
@@ -10,9 +16,12 @@ enum Dir {
     SE,
 }
 
-#[derive(PartialEq)]
-enum Tile {
+#[derive(Debug, Clone, PartialEq)]
+/// The side a tile is flipped to.
+pub enum Tile {
+    /// Black side up.
     Black,
+    /// White side up.
     White,
 }
 
@@ -27,41 +36,50 @@ fn move_dir(pos: Pos, dir: Dir) -> Pos {
     }
 }
 
-fn get_tile_pos(instruction: &str) -> Pos {
+/// Follows a path such as `nwwswee` from the reference tile.
+/// Errors are reported on the first line.
+pub fn get_tile_pos(instruction: &str) -> Result<Pos, ParseError> {
     let mut pos = Pos::new(0, 0);
-    let mut char_iter = instruction.chars().peekable();
+    let mut char_iter = instruction.char_indices().peekable();
+    let unexpected = |i: usize, expected: &str| {
+        let text = instruction[i..].chars().take(2).collect::<String>();
+        ParseError::new(0, instruction[..i].chars().count(), &text, expected)
+    };
     loop {
-        if char_iter.peek().filter(|c| "ns".contains(**c)).is_some() {
-            match (char_iter.next(), char_iter.next()) {
-                (Some('n'), Some('w')) => pos = move_dir(pos, Dir::NW),
-                (Some('n'), Some('e')) => pos = move_dir(pos, Dir::NE),
-                (Some('s'), Some('w')) => pos = move_dir(pos, Dir::SW),
-                (Some('s'), Some('e')) => pos = move_dir(pos, Dir::SE),
-                _ => panic!("Unexpected move symbol."),
-            }
+        if let Some(&(i, c)) = char_iter.peek().filter(|(_, c)| "ns".contains(*c)) {
+            char_iter.next();
+            let dir = match (c, char_iter.next().map(|(_, c)| c)) {
+                ('n', Some('w')) => Dir::NW,
+                ('n', Some('e')) => Dir::NE,
+                ('s', Some('w')) => Dir::SW,
+                ('s', Some('e')) => Dir::SE,
+                _ => return Err(unexpected(i, "`ne`, `nw`, `se` or `sw`")),
+            };
+            pos = move_dir(pos, dir);
         } else {
             match char_iter.next() {
-                Some('w') => pos = move_dir(pos, Dir::W),
-                Some('e') => pos = move_dir(pos, Dir::E),
-                Some(_) => panic!("Unexpected move symbol."),
+                Some((_, 'w')) => pos = move_dir(pos, Dir::W),
+                Some((_, 'e')) => pos = move_dir(pos, Dir::E),
+                Some((i, _)) => return Err(unexpected(i, "a direction")),
                 None => break,
             }
         }
     }
-    pos
+    Ok(pos)
 }
 
-fn flip_tiles(instructions: &str) -> HashMap<Pos, Tile> {
+/// Flips the tile at the end of each path.
+pub fn flip_tiles(instructions: &str) -> Result<HashMap<Pos, Tile>, ParseError> {
     let mut flipped_map: HashMap<Pos, Tile> = HashMap::new();
-    for instruction in instructions.lines() {
-        let pos = get_tile_pos(instruction);
+    for (i, instruction) in instructions.lines().enumerate() {
+        let pos = get_tile_pos(instruction).map_err(|e| e.offset(i))?;
         let entry = flipped_map.entry(pos).or_insert(Tile::White);
         *entry = match entry {
             Tile::Black => Tile::White,
             Tile::White => Tile::Black,
         };
     }
-    flipped_map
+    Ok(flipped_map)
 }
 // Part 2
 fn get_neighboring_tiles(pos: Pos) -> [Pos; 6] {
@@ -75,58 +93,81 @@ fn get_neighboring_tiles(pos: Pos) -> [Pos; 6] {
     ]
 }
 
-fn flip_tiles_part2(tiles: &mut HashMap<Pos, Tile>) {
-    let to_check: HashSet<Pos> = tiles
-        .iter()
-        .filter(|&(_, tile)| *tile == Tile::Black)
-        .flat_map(|(&pos, _)| std::array::IntoIter::new(get_neighboring_tiles(pos)))
-        .collect();
-    let new_tiles = to_check
-        .iter()
-        .map(|&pos| {
-            let count = get_neighboring_tiles(pos)
-                .iter()
-                .filter(|near| matches!(tiles.get(near), Some(Tile::Black)))
-                .count();
-            match (tiles.entry(pos).or_insert(Tile::White), count) {
-                (Tile::Black, 1) | (Tile::Black, 2) | (Tile::White, 2) => (pos, Tile::Black),
-                (Tile::Black, _) | (Tile::White, _) => (pos, Tile::White),
-            }
-        })
-        .collect();
-    *tiles = new_tiles;
+/// The endless hex floor, each tile touching six others.
+#[derive(Clone, Copy, Debug)]
+pub struct HexGrid;
+
+impl Topology for HexGrid {
+    type Cell = Pos;
+    type Neighbors = [Pos; 6];
+
+    fn neighbors(&self, pos: Pos) -> [Pos; 6] {
+        get_neighboring_tiles(pos)
+    }
 }
 
-fn flip_game(mut tiles: HashMap<Pos, Tile>, turns: usize) -> HashMap<Pos, Tile> {
+/// Flips tiles daily for `turns` days. A black tile stays black with one or
+/// two black neighbors, and a white tile turns black with two.
+pub fn flip_game(tiles: HashMap<Pos, Tile>, turns: usize) -> HashMap<Pos, Tile> {
+    let black = tiles.into_iter().filter(|(_, t)| *t == Tile::Black).map(|(p, _)| p);
+    let mut floor = Automaton::new(HexGrid, Rule::new([2], [1, 2]), black);
     for _ in 0..turns {
-        flip_tiles_part2(&mut tiles);
+        floor.step();
+    }
+    floor.live().iter().map(|&pos| (pos, Tile::Black)).collect()
+}
+
+/// Solver for day 24.
+pub struct Day24;
+
+impl Solution for Day24 {
+    type Input = HashMap<Pos, Tile>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        flip_tiles(input)
+    }
+    fn part1(tiles: &Self::Input) -> usize {
+        tiles.values().filter(|v| **v == Tile::Black).count()
+    }
+    fn part2(tiles: &Self::Input) -> usize {
+        let game_map = flip_game(tiles.clone(), 100);
+        game_map.values().filter(|v| **v == Tile::Black).count()
     }
-    tiles
 }
 
 #[cfg(test)]
 mod tests {
     use std::fs;
 
-    use crate::day24::{flip_game, flip_tiles, Tile};
+    use crate::day24::{flip_game, flip_tiles, get_tile_pos, Tile};
     #[test]
     fn example() {
         let inp = fs::read_to_string("input/example24").unwrap();
-        let map = flip_tiles(&inp);
+        let map = flip_tiles(&inp).unwrap();
         assert_eq!(map.iter().filter(|(_, v)| **v == Tile::Black).count(), 10);
     }
 
     #[test]
     fn part1() {
         let inp = fs::read_to_string("input/day24").unwrap();
-        let map = flip_tiles(&inp);
+        let map = flip_tiles(&inp).unwrap();
         assert_eq!(map.iter().filter(|(_, v)| **v == Tile::Black).count(), 434);
     }
 
+    #[test]
+    fn bad_path() {
+        let err = get_tile_pos("esenwx").unwrap_err();
+        assert_eq!((err.column, err.text.as_str()), (6, "x"));
+        let err = flip_tiles("nwe\nesnw").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 2, "sn"));
+    }
+
     #[test]
     fn example2() {
         let inp = fs::read_to_string("input/example24").unwrap();
-        let map = crate::day24::flip_tiles(&inp);
+        let map = crate::day24::flip_tiles(&inp).unwrap();
         let game_map = flip_game(map, 100);
         assert_eq!(
             game_map.iter().filter(|(_, v)| **v == Tile::Black).count(),
@@ -136,7 +177,7 @@ mod tests {
     #[test]
     fn part2() {
         let inp = fs::read_to_string("input/day24").unwrap();
-        let map = crate::day24::flip_tiles(&inp);
+        let map = crate::day24::flip_tiles(&inp).unwrap();
         let game_map = flip_game(map, 100);
         assert_eq!(
             game_map.iter().filter(|(_, v)| **v == Tile::Black).count(),
@@ -1,3 +1,7 @@
+//! Day 25: Combo Breaker.
+use crate::parse::{number, ParseError};
+use crate::solution::{Solution, Unsolved};
+
 const DIV: u64 = 20201227;
 fn transform_loop(mut val: u64, subject: u64) -> u64 {
     val *= subject;
@@ -5,7 +9,8 @@ fn transform_loop(mut val: u64, subject: u64) -> u64 {
     val
 }
 
-fn transform(subject: u64, loop_size: u64) -> u64 {
+/// Transforms `subject` with the given loop size.
+pub fn transform(subject: u64, loop_size: u64) -> u64 {
     let mut val = 1;
     for _ in 0..loop_size {
         val = transform_loop(val, subject);
@@ -13,7 +18,8 @@ fn transform(subject: u64, loop_size: u64) -> u64 {
     val
 }
 
-fn find_loop_size(subject: u64, goal: u64) -> u64 {
+/// Finds the loop size transforming `subject` into `goal`.
+pub fn find_loop_size(subject: u64, goal: u64) -> u64 {
     let mut val = 1;
     for i in 0.. {
         val = transform_loop(val, subject);
@@ -23,6 +29,38 @@ fn find_loop_size(subject: u64, goal: u64) -> u64 {
     }
     unreachable!()
 }
+
+/// Solver for day 25.
+pub struct Day25;
+
+impl Solution for Day25 {
+    /// The card's and the door's public keys.
+    type Input = (u64, u64);
+    type Output1 = u64;
+    type Output2 = Unsolved;
+    /// Day 25 only has one puzzle.
+    const HAS_PART2: bool = false;
+
+    fn parse(input: &str) -> Result<(u64, u64), ParseError> {
+        let mut keys = input.lines().enumerate().map(|(i, l)| {
+            let key = number(i, l, l.trim())?;
+            if key == 0 || key >= DIV {
+                return Err(ParseError::at(i, l, l.trim(), "a key below 20201227"));
+            }
+            Ok(key)
+        });
+        let mut next = || keys.next().unwrap_or_else(|| Err(ParseError::end(input, "a key")));
+        Ok((next()?, next()?))
+    }
+    fn part1(&(card_pub_key, door_pub_key): &(u64, u64)) -> u64 {
+        let card_loop_size = find_loop_size(7, card_pub_key);
+        transform(door_pub_key, card_loop_size)
+    }
+    fn part2(_: &(u64, u64)) -> Unsolved {
+        unreachable!("day 25 has no part 2")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::day25::*;
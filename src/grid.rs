@@ -0,0 +1,342 @@
+use crate::parse::ParseError;
+use std::fmt::{self, Display};
+use std::ops::{Index, IndexMut};
+
+/// Offsets to the four orthogonal neighbors, as `(dx, dy)` with y down.
+pub const NEIGHBORS4: [(i64, i64); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+/// Offsets to the eight neighbors, including diagonals.
+pub const NEIGHBORS8: [(i64, i64); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// A dense rectangular grid, indexed by `(x, y)` with `(0, 0)` top left.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from its cells in row order.
+    ///
+    /// Panics if there are not `width * height` cells.
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(cells.len(), width * height, "grid size mismatch");
+        Grid { width, height, cells }
+    }
+
+    /// Builds a grid by calling `f` with each position.
+    pub fn from_fn(
+        width: usize,
+        height: usize,
+        mut f: impl FnMut((usize, usize)) -> T,
+    ) -> Self {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(&mut f)
+            .collect();
+        Grid { width, height, cells }
+    }
+
+    /// Parses a map with one character per cell, mapping each with `f`.
+    /// Rows must be non-empty and of equal width; a character `f` maps to
+    /// None is reported as not being `expected`.
+    pub fn parse(
+        input: &str,
+        expected: &str,
+        mut f: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        let width = input.lines().next().map_or(0, |l| l.chars().count());
+        if width == 0 {
+            return Err(ParseError::new(0, 0, "", "a row of the map"));
+        }
+        let mut cells = Vec::new();
+        for (i, line) in input.lines().enumerate() {
+            if line.chars().count() != width {
+                let expected = format!("a row of width {}", width);
+                return Err(ParseError::at(i, line, line, expected));
+            }
+            for (j, c) in line.chars().enumerate() {
+                let cell = f(c).ok_or_else(|| {
+                    ParseError::new(i, j, &c.to_string(), expected)
+                })?;
+                cells.push(cell);
+            }
+        }
+        let height = cells.len() / width;
+        Ok(Grid { width, height, cells })
+    }
+
+    /// Number of columns.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    /// Number of rows.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The cell at `(x, y)`, or None if it is outside the grid.
+    pub fn get(&self, (x, y): (usize, usize)) -> Option<&T> {
+        if x < self.width && y < self.height {
+            Some(&self.cells[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    /// The cell at `(x, y)` to change, or None if it is outside the grid.
+    pub fn get_mut(&mut self, (x, y): (usize, usize)) -> Option<&mut T> {
+        if x < self.width && y < self.height {
+            Some(&mut self.cells[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    /// The cell at `(x, y)` on the grid repeated infinitely right and down.
+    pub fn get_wrapping(&self, (x, y): (usize, usize)) -> &T {
+        &self[(x % self.width, y % self.height)]
+    }
+
+    /// The position `(dx, dy)` away from `pos`, if it is inside the grid.
+    pub fn step(
+        &self,
+        (x, y): (usize, usize),
+        (dx, dy): (i64, i64),
+    ) -> Option<(usize, usize)> {
+        let x = x as i64 + dx;
+        let y = y as i64 + dy;
+        if 0 <= x && x < self.width as i64 && 0 <= y && y < self.height as i64
+        {
+            Some((x as usize, y as usize))
+        } else {
+            None
+        }
+    }
+
+    /// The position `(dx, dy)` away from `pos`, wrapping around the edges.
+    pub fn step_wrapping(
+        &self,
+        (x, y): (usize, usize),
+        (dx, dy): (i64, i64),
+    ) -> (usize, usize) {
+        let x = (x as i64 + dx).rem_euclid(self.width as i64);
+        let y = (y as i64 + dy).rem_euclid(self.height as i64);
+        (x as usize, y as usize)
+    }
+
+    /// The cells in row order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    /// The cells in row order, with their positions.
+    pub fn positions(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| ((i % width, i / width), cell))
+    }
+
+    /// The rows, top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width)
+    }
+
+    /// The cells of column `x`, top to bottom.
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    /// The orthogonal neighbors of `pos` inside the grid.
+    pub fn neighbors4(
+        &self,
+        pos: (usize, usize),
+    ) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.around(pos, &NEIGHBORS4)
+    }
+
+    /// The neighbors of `pos` inside the grid, including diagonals.
+    pub fn neighbors8(
+        &self,
+        pos: (usize, usize),
+    ) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.around(pos, &NEIGHBORS8)
+    }
+
+    fn around<'a>(
+        &'a self,
+        pos: (usize, usize),
+        offsets: &'a [(i64, i64)],
+    ) -> impl Iterator<Item = ((usize, usize), &'a T)> {
+        offsets
+            .iter()
+            .filter_map(move |&d| self.step(pos, d))
+            .map(move |p| (p, &self[p]))
+    }
+
+    /// The cells seen looking from `pos` in direction `dir`, nearest
+    /// first, up to the edge of the grid. `pos` itself is not included.
+    pub fn ray(
+        &self,
+        pos: (usize, usize),
+        dir: (i64, i64),
+    ) -> impl Iterator<Item = ((usize, usize), &T)> {
+        let mut pos = Some(pos);
+        std::iter::from_fn(move || {
+            pos = self.step(pos?, dir);
+            pos.map(|p| (p, &self[p]))
+        })
+    }
+
+    /// Renders the grid as a binary PPM image, drawing each cell as a
+    /// `scale` by `scale` square of the color `color` gives it.
+    pub fn to_ppm(&self, scale: usize, color: impl Fn(&T) -> [u8; 3]) -> Vec<u8> {
+        let (width, height) = (self.width * scale, self.height * scale);
+        let mut ppm = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+        for row in self.rows() {
+            let line: Vec<u8> = row
+                .iter()
+                .flat_map(|cell| {
+                    let rgb = color(cell);
+                    std::iter::repeat_n(rgb, scale).flatten()
+                })
+                .collect();
+            for _ in 0..scale {
+                ppm.extend_from_slice(&line);
+            }
+        }
+        ppm
+    }
+
+    /// Applies `f` to every cell.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// The grid turned a quarter clockwise.
+    pub fn rotate(&self) -> Self {
+        let h = self.height;
+        Grid::from_fn(h, self.width, |(x, y)| self[(y, h - 1 - x)].clone())
+    }
+
+    /// The grid mirrored left to right.
+    pub fn flip(&self) -> Self {
+        let w = self.width;
+        Grid::from_fn(w, self.height, |(x, y)| self[(w - 1 - x, y)].clone())
+    }
+
+    /// The eight rotations and reflections of the grid: zero to three
+    /// quarter turns clockwise, then the same turns of the flipped grid.
+    pub fn orientations(&self) -> Vec<Self> {
+        let mut grids = Vec::with_capacity(8);
+        for grid in [self.clone(), self.flip()] {
+            let mut grid = grid;
+            for _ in 0..4 {
+                let next = grid.rotate();
+                grids.push(grid);
+                grid = next;
+            }
+        }
+        grids
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+    fn index(&self, pos: (usize, usize)) -> &T {
+        self.get(pos).expect("position outside the grid")
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, pos: (usize, usize)) -> &mut T {
+        self.get_mut(pos).expect("position outside the grid")
+    }
+}
+
+/// Prints one line per row, with no separators between cells.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+    fn grid() -> Grid<char> {
+        Grid::parse("ab\ncd\nef", "a letter", Some).unwrap()
+    }
+    #[test]
+    fn access() {
+        let g = grid();
+        assert_eq!((g.width(), g.height()), (2, 3));
+        assert_eq!(g[(1, 2)], 'f');
+        assert_eq!(g.get((2, 0)), None);
+        assert_eq!(*g.get_wrapping((3, 4)), 'd');
+        assert_eq!(g.step_wrapping((0, 0), (-1, -1)), (1, 2));
+        assert_eq!(g.column(1).collect::<String>(), "bdf");
+        let n: String = g.neighbors4((0, 1)).map(|(_, c)| c).collect();
+        assert_eq!(n, "ade");
+        let n: String = g.neighbors8((0, 1)).map(|(_, c)| c).collect();
+        assert_eq!(n, "abdef");
+        let ray: String = g.ray((0, 0), (1, 1)).map(|(_, c)| c).collect();
+        assert_eq!(ray, "d");
+        let ray: Vec<_> = g.ray((1, 0), (0, 1)).map(|(p, _)| p).collect();
+        assert_eq!(ray, [(1, 1), (1, 2)]);
+    }
+    #[test]
+    fn transform() {
+        let g = grid();
+        assert_eq!(g.to_string(), "ab\ncd\nef");
+        assert_eq!(g.rotate().to_string(), "eca\nfdb");
+        assert_eq!(g.flip().to_string(), "ba\ndc\nfe");
+        assert_eq!(g.rotate().rotate().rotate().rotate(), g);
+        let all = g.orientations();
+        assert_eq!((all.len(), &all[0], &all[4]), (8, &g, &g.flip()));
+        assert_eq!(all[7].to_string(), "ace\nbdf");
+    }
+    #[test]
+    fn ppm() {
+        let g = Grid::new(2, 1, vec![true, false]);
+        let ppm = g.to_ppm(2, |&b| if b { [255, 0, 0] } else { [0; 3] });
+        let header = b"P6\n4 2\n255\n";
+        assert_eq!(&ppm[..header.len()], header);
+        let row = [255, 0, 0, 255, 0, 0, 0, 0, 0, 0, 0, 0];
+        assert_eq!(&ppm[header.len()..], [row, row].concat());
+    }
+    #[test]
+    fn parse_errors() {
+        let digit = |c: char| c.to_digit(10);
+        let err = Grid::parse("12\n3x", "a digit", digit).unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 2, "x"));
+        let err = Grid::parse("12\n345", "a digit", digit).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert!(Grid::parse("", "a digit", digit).is_err());
+    }
+}
@@ -1,26 +1,119 @@
-#![allow(dead_code)]
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day20;
-mod day21;
-mod day22;
-mod day23;
-mod day24;
-mod day25;
+//! Solutions to Advent of Code 2020.
+//!
+//! Each `dayNN` module holds the solvers for that day's puzzle. Every day
+//! also implements [`Solution`], and [`DAYS`] lists them all so they can be
+//! run uniformly:
+//!
+//! ```no_run
+//! let input = std::fs::read_to_string("input/day01").unwrap();
+//! let puzzle = aoc_2020::day(1).unwrap().parse(&input).unwrap();
+//! println!("{}", puzzle.part1());
+//! ```
+#![warn(missing_docs)]
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
+mod answers;
+mod automaton;
+mod grid;
+mod parse;
+mod pattern;
+mod solution;
+
+pub use answers::Answers;
+pub use automaton::{
+    Automaton, DenseAutomaton, Graph, Lattice, Mirrored, Rule, Settled, Topology,
+};
+pub use grid::{Grid, NEIGHBORS4, NEIGHBORS8};
+pub use parse::ParseError;
+pub use pattern::{Match, Pattern, Search};
+pub use solution::{Day, Puzzle, Solution, Unsolved};
+
+/// Every day's solution, in order.
+pub const DAYS: [Day; 25] = [
+    Day::new::<day01::Day01>(1),
+    Day::new::<day02::Day02>(2),
+    Day::new::<day03::Day03>(3),
+    Day::new::<day04::Day04>(4),
+    Day::new::<day05::Day05>(5),
+    Day::new::<day06::Day06>(6),
+    Day::new::<day07::Day07>(7),
+    Day::new::<day08::Day08>(8),
+    Day::new::<day09::Day09>(9),
+    Day::new::<day10::Day10>(10),
+    Day::new::<day11::Day11>(11),
+    Day::new::<day12::Day12>(12),
+    Day::new::<day13::Day13>(13),
+    Day::new::<day14::Day14>(14),
+    Day::new::<day15::Day15>(15),
+    Day::new::<day16::Day16>(16),
+    Day::new::<day17::Day17>(17),
+    Day::new::<day18::Day18>(18),
+    Day::new::<day19::Day19>(19),
+    Day::new::<day20::Day20>(20),
+    Day::new::<day21::Day21>(21),
+    Day::new::<day22::Day22>(22),
+    Day::new::<day23::Day23>(23),
+    Day::new::<day24::Day24>(24),
+    Day::new::<day25::Day25>(25),
+];
+
+/// Looks up the solution for `day`.
+pub fn day(day: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.day == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{day, Answers, DAYS};
+    #[test]
+    fn registry() {
+        for (i, d) in DAYS.iter().enumerate() {
+            assert_eq!(d.day as usize, i + 1);
+        }
+        assert!(day(26).is_none());
+    }
+    #[test]
+    fn example() {
+        let input = fs::read_to_string("input/example01").unwrap();
+        let ans = day(1).unwrap().solve(1, &input).unwrap();
+        assert_eq!(ans.as_deref(), Some("514579"));
+        let ans = day(25).unwrap().solve(2, "5764801\n17807724").unwrap();
+        assert_eq!(ans, None);
+        let err = day(1).unwrap().solve(1, "1721\n979\nabc").unwrap_err();
+        assert_eq!((err.line, err.column), (3, 1));
+    }
+    #[test]
+    fn answers() {
+        let answers = Answers::parse(&fs::read_to_string("answers.txt").unwrap());
+        let answers = answers.unwrap();
+        for d in DAYS.iter() {
+            let input = format!("input/day{:02}", d.day);
+            assert!(answers.get(d.day, &input, 1).is_some(), "day {}", d.day);
+        }
+    }
+}
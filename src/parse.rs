@@ -0,0 +1,151 @@
+use std::{error::Error, fmt, str::FromStr};
+
+/// A malformed puzzle input, pointing at the offending text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// One-based line number.
+    pub line: usize,
+    /// One-based column, counted in characters.
+    pub column: usize,
+    /// The offending text.
+    pub text: String,
+    /// What was expected in its place.
+    pub expected: String,
+}
+
+impl ParseError {
+    /// Error for `text` at the zero-based `line` and `column`.
+    pub fn new(
+        line: usize,
+        column: usize,
+        text: &str,
+        expected: impl Into<String>,
+    ) -> Self {
+        ParseError {
+            line: line + 1,
+            column: column + 1,
+            text: text.to_string(),
+            expected: expected.into(),
+        }
+    }
+    /// Error for `text`, a slice of `line_text`, on the zero-based `line`.
+    /// The column is worked out from where `text` sits in `line_text`; use
+    /// an empty slice at the end of the line for something missing.
+    pub(crate) fn at(
+        line: usize,
+        line_text: &str,
+        text: &str,
+        expected: impl Into<String>,
+    ) -> Self {
+        Self::new(line, column_of(line_text, text), text, expected)
+    }
+    /// Error for something missing at the end of `input`.
+    pub(crate) fn end(input: &str, expected: impl Into<String>) -> Self {
+        let line = input.matches('\n').count();
+        let column = input.rsplit('\n').next().unwrap_or("").chars().count();
+        Self::new(line, column, "", expected)
+    }
+    /// Moves the error down by `lines` lines, for errors from parsing a part
+    /// of a larger input.
+    pub(crate) fn offset(mut self, lines: usize) -> Self {
+        self.line += lines;
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}, found {:?}",
+            self.line, self.column, self.expected, self.text
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+/// Zero-based character column of `text` in `line_text`. Falls back to the
+/// end of the line if `text` is not a slice of it.
+fn column_of(line_text: &str, text: &str) -> usize {
+    let start = line_text.as_ptr() as usize;
+    let pos = text.as_ptr() as usize;
+    if start <= pos && pos <= start + line_text.len() {
+        line_text[..pos - start].chars().count()
+    } else {
+        line_text.chars().count()
+    }
+}
+
+/// Splits `input` into sections separated by blank lines, returning each
+/// with the zero-based line it starts on. Works with LF and CRLF line
+/// endings; lines of only whitespace count as blank, and leading or trailing
+/// blank lines give no empty sections. Each section is a slice of `input`
+/// without its final line ending.
+pub(crate) fn sections(input: &str) -> Vec<(usize, &str)> {
+    let mut result = Vec::new();
+    let mut start = None;
+    let (mut pos, mut end) = (0, 0);
+    for (i, raw) in input.split_inclusive('\n').enumerate() {
+        let line = raw.trim_end_matches('\n').trim_end_matches('\r');
+        if line.trim().is_empty() {
+            if let Some((first, from)) = start.take() {
+                result.push((first, &input[from..end]));
+            }
+        } else {
+            start.get_or_insert((i, pos));
+            end = pos + line.len();
+        }
+        pos += raw.len();
+    }
+    if let Some((first, from)) = start {
+        result.push((first, &input[from..end]));
+    }
+    result
+}
+
+/// Parses `text`, a slice of `line_text`, as a number.
+pub(crate) fn number<T: FromStr>(
+    line: usize,
+    line_text: &str,
+    text: &str,
+) -> Result<T, ParseError> {
+    text.parse().map_err(|_| ParseError::at(line, line_text, text, "a number"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{number, sections, ParseError};
+    #[test]
+    fn column() {
+        let line = "mem[8] = 11x";
+        let err = number::<usize>(4, line, &line[9..]).unwrap_err();
+        assert_eq!((err.line, err.column), (5, 10));
+        assert_eq!(
+            err.to_string(),
+            "line 5, column 10: expected a number, found \"11x\""
+        );
+        let err = ParseError::at(0, line, &line[line.len()..], "more");
+        assert_eq!((err.line, err.column), (1, 13));
+    }
+    #[test]
+    fn offset() {
+        let err = ParseError::new(0, 0, "c", "d").offset(3);
+        assert_eq!(err.line, 4);
+        let err = ParseError::end("a\nbc", "d");
+        assert_eq!((err.line, err.column), (2, 3));
+        let err = ParseError::end("a\nbc\n", "d");
+        assert_eq!((err.line, err.column), (3, 1));
+    }
+    #[test]
+    fn section_split() {
+        let expected = vec![(0, "a\nb"), (3, "c")];
+        assert_eq!(sections("a\nb\n\nc\n"), expected);
+        assert_eq!(sections("a\nb\n \t\nc"), expected);
+        assert_eq!(sections("\na\nb\n\n\nc\n\n").len(), 2);
+        let crlf = sections("a\r\nb\r\n\r\nc\r\n");
+        assert_eq!(crlf, vec![(0, "a\r\nb"), (3, "c")]);
+        assert_eq!(crlf[0].1.lines().collect::<Vec<_>>(), ["a", "b"]);
+        assert!(sections(" \r\n\n").is_empty());
+    }
+}
@@ -0,0 +1,174 @@
+use crate::grid::Grid;
+use crate::parse::ParseError;
+use std::collections::HashSet;
+
+/// A shape to look for in a grid, drawn in ASCII with `#` for the cells
+/// that must be set and `.` or space for cells that can be anything.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pattern {
+    width: usize,
+    height: usize,
+    cells: Vec<(usize, usize)>,
+}
+
+/// Where a pattern was found.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Match {
+    /// Index of the pattern's orientation, in the order of
+    /// [`Grid::orientations`].
+    pub orientation: usize,
+    /// Top left corner of the oriented pattern in the grid.
+    pub position: (usize, usize),
+    /// The grid cells under the pattern's `#`s.
+    pub cells: Vec<(usize, usize)>,
+}
+
+/// All matches of a pattern in a grid.
+#[derive(Clone, Debug, Default)]
+pub struct Search {
+    /// The matches, by orientation then position.
+    pub matches: Vec<Match>,
+    /// Every cell covered by some match.
+    pub covered: HashSet<(usize, usize)>,
+}
+
+impl Pattern {
+    /// Rows may differ in length; the pattern is as wide as the longest.
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut cells = Vec::new();
+        for (y, line) in input.lines().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                match c {
+                    '#' => cells.push((x, y)),
+                    '.' | ' ' => (),
+                    _ => {
+                        let expected = "`#`, `.` or space";
+                        let c = c.to_string();
+                        return Err(ParseError::new(y, x, &c, expected));
+                    }
+                }
+            }
+        }
+        if cells.is_empty() {
+            return Err(ParseError::end(input, "a `#`"));
+        }
+        let width = input.lines().map(|l| l.chars().count()).max().unwrap_or(0);
+        let height = input.lines().count();
+        Ok(Pattern {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// Width of the widest row.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    /// Number of rows.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The pattern turned and flipped like [`Grid::orientations`].
+    pub fn orientations(&self) -> Vec<Pattern> {
+        let mut grid = Grid::new(
+            self.width,
+            self.height,
+            vec![false; self.width * self.height],
+        );
+        for &pos in &self.cells {
+            grid[pos] = true;
+        }
+        grid.orientations()
+            .into_iter()
+            .map(|grid| Pattern {
+                width: grid.width(),
+                height: grid.height(),
+                cells: grid
+                    .positions()
+                    .filter(|(_, &b)| b)
+                    .map(|(p, _)| p)
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// The top left corners of the places where every `#` of the pattern,
+    /// as it is oriented, lies on a cell of `grid` that `is_set` accepts.
+    pub fn find<T>(
+        &self,
+        grid: &Grid<T>,
+        is_set: impl Fn(&T) -> bool,
+    ) -> Vec<(usize, usize)> {
+        if self.width > grid.width() || self.height > grid.height() {
+            return Vec::new();
+        }
+        let mut found = Vec::new();
+        for y in 0..=grid.height() - self.height {
+            for x in 0..=grid.width() - self.width {
+                if self
+                    .cells
+                    .iter()
+                    .all(|&(dx, dy)| is_set(&grid[(x + dx, y + dy)]))
+                {
+                    found.push((x, y));
+                }
+            }
+        }
+        found
+    }
+
+    /// Finds the pattern in every orientation. A symmetric pattern matches
+    /// the same cells in more than one orientation.
+    pub fn search<T>(
+        &self,
+        grid: &Grid<T>,
+        is_set: impl Fn(&T) -> bool,
+    ) -> Search {
+        let mut search = Search::default();
+        for (orientation, pattern) in self.orientations().iter().enumerate() {
+            for position in pattern.find(grid, &is_set) {
+                let (x, y) = position;
+                let cells: Vec<_> = pattern
+                    .cells
+                    .iter()
+                    .map(|&(dx, dy)| (x + dx, y + dy))
+                    .collect();
+                search.covered.extend(&cells);
+                search.matches.push(Match {
+                    orientation,
+                    position,
+                    cells,
+                });
+            }
+        }
+        search
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Pattern;
+    use crate::grid::Grid;
+    #[test]
+    fn search() {
+        let pattern = Pattern::parse("##\n#").unwrap();
+        assert_eq!((pattern.width(), pattern.height()), (2, 2));
+        let grid = Grid::parse("...#\n..##\n#...", "a cell", Some).unwrap();
+        assert_eq!(pattern.find(&grid, |&c| c == '#'), []);
+        let search = pattern.search(&grid, |&c| c == '#');
+        // Turned twice, or flipped and turned once.
+        assert_eq!(search.matches.len(), 2);
+        let found = &search.matches[0];
+        assert_eq!((found.orientation, found.position), (2, (2, 0)));
+        assert_eq!(search.matches[1].orientation, 5);
+        assert_eq!(found.cells, [(3, 0), (2, 1), (3, 1)]);
+        assert_eq!(search.covered.len(), 3);
+        let dot = Pattern::parse("#").unwrap();
+        assert_eq!(dot.search(&grid, |&c| c == '#').matches.len(), 32);
+        let err = Pattern::parse("#\n x").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+        assert!(Pattern::parse(" .").is_err());
+    }
+}
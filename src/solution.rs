@@ -0,0 +1,91 @@
+use crate::parse::ParseError;
+use std::fmt::{self, Display};
+
+/// A day's puzzle. The input is parsed once and shared by both parts.
+pub trait Solution {
+    /// The parsed input.
+    type Input;
+    /// The answer to part 1.
+    type Output1: Display;
+    /// The answer to part 2.
+    type Output2: Display;
+    /// False for days where part 2 has no solver.
+    const HAS_PART2: bool = true;
+
+    /// Parses the puzzle input.
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+    /// Solves part 1.
+    fn part1(input: &Self::Input) -> Self::Output1;
+    /// Solves part 2.
+    fn part2(input: &Self::Input) -> Self::Output2;
+}
+
+/// Answer type for a part that has no solver. It has no values, so a
+/// `part2` returning it can never return.
+pub enum Unsolved {}
+
+impl Display for Unsolved {
+    fn fmt(&self, _: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {}
+    }
+}
+
+/// Parsed input of some day, with the answer types erased.
+pub trait Puzzle {
+    /// Solves part 1.
+    fn part1(&self) -> String;
+    /// None if the day has no solver for part 2.
+    fn part2(&self) -> Option<String>;
+}
+
+struct Parsed<S: Solution>(S::Input);
+
+impl<S: Solution> Puzzle for Parsed<S> {
+    fn part1(&self) -> String {
+        S::part1(&self.0).to_string()
+    }
+    fn part2(&self) -> Option<String> {
+        if S::HAS_PART2 {
+            Some(S::part2(&self.0).to_string())
+        } else {
+            None
+        }
+    }
+}
+
+fn parse_erased<S: Solution + 'static>(
+    input: &str,
+) -> Result<Box<dyn Puzzle>, ParseError> {
+    Ok(Box::new(Parsed::<S>(S::parse(input)?)))
+}
+
+/// Registry entry, pairing a day number with its solution.
+pub struct Day {
+    /// The day of December.
+    pub day: u8,
+    parse: fn(&str) -> Result<Box<dyn Puzzle>, ParseError>,
+}
+
+impl Day {
+    /// The entry for day `day`, solved by `S`.
+    pub const fn new<S: Solution + 'static>(day: u8) -> Self {
+        Day { day, parse: parse_erased::<S> }
+    }
+    /// Parses the input of the day.
+    pub fn parse(&self, input: &str) -> Result<Box<dyn Puzzle>, ParseError> {
+        (self.parse)(input)
+    }
+    /// Solves `part` (1 or 2). Returns None if there is no such part.
+    pub fn solve(
+        &self,
+        part: u8,
+        input: &str,
+    ) -> Result<Option<String>, ParseError> {
+        let puzzle = self.parse(input)?;
+        Ok(match part {
+            1 => Some(puzzle.part1()),
+            2 => puzzle.part2(),
+            _ => None,
+        })
+    }
+}